@@ -1,7 +1,9 @@
 use anyhow::Result;
+use icu_properties::{maps, script, sets, BidiClass, EastAsianWidth, GeneralCategory, GeneralCategoryGroup, GraphemeClusterBreak, Script};
 use icu_segmenter::GraphemeClusterSegmenter;
 use serde::{Deserialize, Serialize};
-use unicode_names2;
+
+mod ucd_tables;
 
 pub fn iter_grapheme_icu4x(input: &str) -> Result<Vec<String>> {
     let segmenter = GraphemeClusterSegmenter::new();
@@ -83,19 +85,129 @@ pub fn drop_units(input: &str, mode: ProcessingMode, n: usize) -> Result<Vec<Str
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CharProperties {
+    pub general_category: String,
+    pub script: String,
+    pub script_extensions: Vec<String>,
+    pub block: String,
+    pub bidi_class: String,
+    pub east_asian_width: String,
+    pub canonical_combining_class: u8,
+    pub grapheme_cluster_break: String,
+    pub age: Option<String>,
+    pub numeric_value: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CodepointInfo {
     pub index: usize,
     pub char: String,
     pub codepoint: String,
     pub name: String,
+    pub label: Option<CodepointLabel>,
+    pub aliases: Vec<String>,
+    pub utf8: String,
+    pub utf16: String,
+    pub special: Option<SpecialCharKind>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unihan: Option<UnihanInfo>,
+    #[serde(flatten)]
+    pub properties: CharProperties,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnihanInfo {
+    pub mandarin: Option<String>,
+    pub jis_x0208: Option<String>,
+    pub shift_jis: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CodepointLabel {
+    Control,
+    PrivateUse,
+    Surrogate,
+    Noncharacter,
+    Reserved,
+}
+
+impl CodepointLabel {
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            CodepointLabel::Control => "control",
+            CodepointLabel::PrivateUse => "private-use",
+            CodepointLabel::Surrogate => "surrogate",
+            CodepointLabel::Noncharacter => "noncharacter",
+            CodepointLabel::Reserved => "reserved",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpecialCharKind {
+    Control,
+    Joiner,
+    VariationSelector,
+    BidiControl,
+    CombiningMark,
+    Whitespace,
+    PrivateUse,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClusterInfo {
     pub cluster_index: usize,
     pub display: String,
+    pub byte_offset: usize,
+    pub codepoint_offset: usize,
+    pub utf16_offset: usize,
     pub codepoints: Vec<CodepointInfo>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<EmojiInfo>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub invalid: Option<InvalidSequenceInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmojiInfo {
+    pub sequence_type: EmojiSequenceType,
+    pub name: Option<String>,
+    pub rgi: bool,
+    pub emoji_presentation: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmojiSequenceType {
+    Basic,
+    Keycap,
+    Flag,
+    Tag,
+    Modifier,
+    Zwj,
+}
+
+impl EmojiSequenceType {
+    pub fn description(&self) -> &'static str {
+        match self {
+            EmojiSequenceType::Basic => "basic emoji",
+            EmojiSequenceType::Keycap => "keycap sequence",
+            EmojiSequenceType::Flag => "flag sequence",
+            EmojiSequenceType::Tag => "tag sequence",
+            EmojiSequenceType::Modifier => "modifier sequence",
+            EmojiSequenceType::Zwj => "ZWJ sequence",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvalidSequenceInfo {
+    pub bytes: String,
+    pub kind: Utf8ErrorKind,
+    pub reason: String,
 }
 
 #[derive(Debug, Clone)]
@@ -103,68 +215,543 @@ pub enum DumpFormat {
     Text,
     Json,
     Jsonl,
+    Csv,
+    Tsv,
+    Markdown,
+    Html,
+}
+
+#[derive(Debug, Clone)]
+pub struct DumpOptions {
+    pub header: bool,
+    pub lang: Option<String>,
+    pub color: bool,
+    pub unihan: bool,
+    pub filter: DumpFilter,
+}
+
+impl Default for DumpOptions {
+    fn default() -> Self {
+        DumpOptions { header: true, lang: None, color: false, unihan: false, filter: DumpFilter::default() }
+    }
+}
+
+// Clusters must match every filter that is set; cluster indices and offsets are those of the
+// unfiltered input. Category accepts short or long names and groups ("Cf", "Format", "L"),
+// script accepts short or long names ("Cyrl", "Cyrillic") and also matches Script_Extensions.
+#[derive(Debug, Clone, Default)]
+pub struct DumpFilter {
+    pub non_ascii: bool,
+    pub multi_codepoint: bool,
+    pub invisible: bool,
+    pub category: Option<String>,
+    pub script: Option<String>,
+}
+
+// Short or long names of a general category or category group ("Lu", "Uppercase_Letter", "L")
+fn parse_general_category(name: &str) -> Result<GeneralCategoryGroup> {
+    GeneralCategoryGroup::name_to_enum_mapper()
+        .get_loose(name)
+        .ok_or_else(|| anyhow::anyhow!("Unknown general category: {}", name))
+}
+
+#[derive(Default)]
+struct ClusterFilter {
+    non_ascii: bool,
+    multi_codepoint: bool,
+    invisible: bool,
+    category: Option<GeneralCategoryGroup>,
+    script: Option<Script>,
+}
+
+impl ClusterFilter {
+    fn new(filter: &DumpFilter) -> Result<Self> {
+        let category = filter.category.as_deref().map(parse_general_category).transpose()?;
+        let script = filter
+            .script
+            .as_deref()
+            .map(|name| {
+                Script::name_to_enum_mapper()
+                    .get_loose(name)
+                    .ok_or_else(|| anyhow::anyhow!("Unknown script: {}", name))
+            })
+            .transpose()?;
+        Ok(ClusterFilter {
+            non_ascii: filter.non_ascii,
+            multi_codepoint: filter.multi_codepoint,
+            invisible: filter.invisible,
+            category,
+            script,
+        })
+    }
+
+    fn matches(&self, cluster: &str) -> bool {
+        let script_data = script::script_with_extensions();
+        (!self.non_ascii || !cluster.is_ascii())
+            && (!self.multi_codepoint || cluster.chars().nth(1).is_some())
+            && (!self.invisible || cluster.chars().any(is_invisible))
+            && self
+                .category
+                .is_none_or(|group| cluster.chars().any(|ch| group.contains(maps::general_category().get(ch))))
+            && self
+                .script
+                .is_none_or(|script| cluster.chars().any(|ch| script_data.has_script(ch as u32, script)))
+    }
+
+    // Invalid sequences have no code points, so they only pass the non-ASCII filter
+    fn matches_invalid(&self) -> bool {
+        !self.multi_codepoint && !self.invisible && self.category.is_none() && self.script.is_none()
+    }
 }
 
 pub fn get_unicode_name(ch: char) -> String {
-    unicode_names2::name(ch)
-        .map(|name| name.to_string())
-        .unwrap_or_else(|| format!("UNKNOWN CHARACTER"))
+    if let Some(name) = character_name(ch) {
+        return name;
+    }
+    match get_codepoint_label(ch as u32) {
+        Some(label) => format_codepoint_label(label, ch as u32),
+        None => "UNKNOWN CHARACTER".to_string(),
+    }
+}
+
+// The Name property. unicode_names2 covers Unicode 15.0; names of the 15.1 additions come
+// from the UCD tables, and ideographs are named after their code point (section 4.8).
+fn character_name(ch: char) -> Option<String> {
+    if let Some(name) = unicode_names2::name(ch) {
+        return Some(name.to_string());
+    }
+    if let Ok(i) = ucd_tables::NAMES.binary_search_by_key(&(ch as u32), |&(codepoint, _)| codepoint) {
+        return Some(ucd_tables::NAMES[i].1.to_string());
+    }
+    if sets::unified_ideograph().contains(ch) {
+        return Some(format!("CJK UNIFIED IDEOGRAPH-{:04X}", ch as u32));
+    }
+    if maps::script().get(ch) == Script::Tangut && maps::general_category().get(ch) == GeneralCategory::OtherLetter {
+        return Some(format!("TANGUT IDEOGRAPH-{:04X}", ch as u32));
+    }
+    None
+}
+
+// Code point labels (Unicode 15.1, section 4.8) for code points without a Name.
+// Takes a u32 so that surrogates, which are not valid chars, can be labeled too.
+pub fn get_codepoint_label(codepoint: u32) -> Option<CodepointLabel> {
+    if (0xD800..=0xDFFF).contains(&codepoint) {
+        return Some(CodepointLabel::Surrogate);
+    }
+    let ch = char::from_u32(codepoint)?;
+    match maps::general_category().get(ch) {
+        GeneralCategory::Control => Some(CodepointLabel::Control),
+        GeneralCategory::PrivateUse => Some(CodepointLabel::PrivateUse),
+        _ if sets::noncharacter_code_point().contains(ch) => Some(CodepointLabel::Noncharacter),
+        GeneralCategory::Unassigned => Some(CodepointLabel::Reserved),
+        _ => None,
+    }
+}
+
+pub fn format_codepoint_label(label: CodepointLabel, codepoint: u32) -> String {
+    format!("<{}-{:04X}>", label.prefix(), codepoint)
+}
+
+pub fn get_name_aliases(ch: char) -> Vec<String> {
+    ucd_tables::NAME_ALIASES
+        .binary_search_by_key(&(ch as u32), |&(codepoint, _)| codepoint)
+        .map(|i| ucd_tables::NAME_ALIASES[i].1.iter().map(|alias| alias.to_string()).collect())
+        .unwrap_or_default()
+}
+
+// Only unified ideographs get a Unihan section; other characters return None
+pub fn get_unihan_info(ch: char) -> Option<UnihanInfo> {
+    if !sets::unified_ideograph().contains(ch) {
+        return None;
+    }
+    let mandarin = ucd_tables::MANDARIN
+        .binary_search_by_key(&(ch as u32), |&(codepoint, _)| codepoint)
+        .ok()
+        .map(|i| ucd_tables::MANDARIN[i].1.to_string());
+
+    let mut buffer = [0u8; 4];
+    let (euc, _, euc_unmappable) = encoding_rs::EUC_JP.encode(ch.encode_utf8(&mut buffer));
+    // EUC-JP puts JIS X 0208 row and cell in the two bytes, offset by 0xA0. Rows above 84
+    // are the IBM extensions in the WHATWG index, which are not part of JIS X 0208.
+    let row_cell = match &euc[..] {
+        &[row, cell] if !euc_unmappable && (0xA1..=0xA0 + 84).contains(&row) => Some((row - 0xA0, cell - 0xA0)),
+        _ => None,
+    };
+
+    Some(UnihanInfo {
+        mandarin,
+        jis_x0208: row_cell.map(|(row, cell)| format!("{:02}-{:02}", row, cell)),
+        shift_jis: row_cell.map(|(row, cell)| format_hex_bytes(&jis_x0208_to_shift_jis(row, cell))),
+    })
+}
+
+// Shift_JIS packs two rows into each lead byte, so vendor extensions such as the IBM
+// kanji of Windows-31J (FA40..FC4B) never come out of it
+fn jis_x0208_to_shift_jis(row: u8, cell: u8) -> [u8; 2] {
+    let lead = row.div_ceil(2) + if row <= 62 { 0x80 } else { 0xC0 };
+    let trail = match (row % 2, cell) {
+        (1, 1..=63) => cell + 0x3F,
+        (1, _) => cell + 0x40,
+        _ => cell + 0x9E,
+    };
+    [lead, trail]
+}
+
+pub fn get_char_properties(ch: char) -> CharProperties {
+    let general_category = maps::general_category().get(ch);
+    let script_data = script::script_with_extensions();
+    let script = script_data.get_script_val(ch as u32);
+    let script_extensions = script_data
+        .get_script_extensions_val(ch as u32)
+        .iter()
+        .map(script_name)
+        .collect();
+
+    CharProperties {
+        general_category: GeneralCategory::enum_to_long_name_mapper()
+            .get(general_category)
+            .unwrap_or("Unassigned")
+            .to_string(),
+        script: script_name(script),
+        script_extensions,
+        block: unicode_blocks::find_unicode_block(ch)
+            .map(|block| block.name().to_string())
+            .unwrap_or_else(|| "No_Block".to_string()),
+        bidi_class: BidiClass::enum_to_long_name_mapper()
+            .get(maps::bidi_class().get(ch))
+            .unwrap_or("Left_To_Right")
+            .to_string(),
+        east_asian_width: EastAsianWidth::enum_to_long_name_mapper()
+            .get(maps::east_asian_width().get(ch))
+            .unwrap_or("Neutral")
+            .to_string(),
+        canonical_combining_class: maps::canonical_combining_class().get(ch).0,
+        grapheme_cluster_break: GraphemeClusterBreak::enum_to_long_name_mapper()
+            .get(maps::grapheme_cluster_break().get(ch))
+            .unwrap_or("Other")
+            .to_string(),
+        age: lookup_range(ucd_tables::AGE, ch as u32)
+            .map(|&(_, _, age)| age.to_string()),
+        numeric_value: lookup_range(ucd_tables::NUMERIC_VALUE, ch as u32)
+            .map(|&(_, _, value)| value.to_string()),
+    }
+}
+
+fn script_name(script: Script) -> String {
+    Script::enum_to_long_name_mapper()
+        .get(script)
+        .unwrap_or("Unknown")
+        .to_string()
+}
+
+fn lookup_range<T>(table: &'static [(u32, u32, T)], codepoint: u32) -> Option<&'static (u32, u32, T)> {
+    table
+        .binary_search_by(|&(start, end, _)| {
+            if codepoint < start {
+                std::cmp::Ordering::Greater
+            } else if codepoint > end {
+                std::cmp::Ordering::Less
+            } else {
+                std::cmp::Ordering::Equal
+            }
+        })
+        .ok()
+        .map(|i| &table[i])
 }
 
 pub fn dump_graphemes(input: &str, format: DumpFormat) -> Result<String> {
-    let segments = iter_grapheme_icu4x(input)?;
-    let mut clusters = Vec::new();
-    
-    for (cluster_index, segment) in segments.iter().enumerate() {
-        let mut codepoints = Vec::new();
-        
-        for (index, ch) in segment.chars().enumerate() {
-            let codepoint_info = CodepointInfo {
-                index,
-                char: if ch.is_control() || ch == '\u{200D}' {
-                    format!("\\u{:04x}", ch as u32)
-                } else {
-                    ch.to_string()
-                },
-                codepoint: format!("U+{:04X}", ch as u32),
-                name: get_unicode_name(ch),
-            };
-            codepoints.push(codepoint_info);
+    dump_graphemes_with_options(input, format, &DumpOptions::default())
+}
+
+pub fn dump_graphemes_with_options(input: &str, format: DumpFormat, options: &DumpOptions) -> Result<String> {
+    let mut builder = ClusterBuilder::new(options)?;
+    builder.push_text(input)?;
+    format_clusters(&builder.clusters, input, format, options)
+}
+
+pub fn dump_bytes(bytes: &[u8], format: DumpFormat) -> Result<String> {
+    dump_bytes_with_options(bytes, format, &DumpOptions::default())
+}
+
+pub fn dump_bytes_with_options(bytes: &[u8], format: DumpFormat, options: &DumpOptions) -> Result<String> {
+    let mut builder = ClusterBuilder::new(options)?;
+    for segment in utf8_segments(bytes) {
+        match segment {
+            Utf8Segment::Valid { text, .. } => builder.push_text(text)?,
+            Utf8Segment::Invalid(sequence) => builder.push_invalid(&sequence),
         }
-        
-        let cluster_info = ClusterInfo {
-            cluster_index,
-            display: segment.clone(),
-            codepoints,
-        };
-        clusters.push(cluster_info);
     }
-    
+    format_clusters(&builder.clusters, &String::from_utf8_lossy(bytes), format, options)
+}
+
+fn format_clusters(clusters: &[ClusterInfo], input: &str, format: DumpFormat, options: &DumpOptions) -> Result<String> {
     match format {
-        DumpFormat::Text => format_text_output(&clusters),
-        DumpFormat::Json => format_json_output(&clusters),
-        DumpFormat::Jsonl => format_jsonl_output(&clusters),
+        DumpFormat::Text => format_text_output(clusters, options.color),
+        DumpFormat::Json => format_json_output(clusters),
+        DumpFormat::Jsonl => format_jsonl_output(clusters),
+        DumpFormat::Csv => format_delimited_output(clusters, ',', options.header),
+        DumpFormat::Tsv => format_delimited_output(clusters, '\t', options.header),
+        DumpFormat::Markdown => format_markdown_output(clusters),
+        DumpFormat::Html => format_html_output(clusters, input, options.lang.as_deref()),
+    }
+}
+
+#[derive(Default)]
+struct ClusterBuilder {
+    clusters: Vec<ClusterInfo>,
+    byte_offset: usize,
+    codepoint_offset: usize,
+    utf16_offset: usize,
+    cluster_count: usize,
+    unihan: bool,
+    filter: ClusterFilter,
+}
+
+impl ClusterBuilder {
+    fn new(options: &DumpOptions) -> Result<Self> {
+        Ok(ClusterBuilder { unihan: options.unihan, filter: ClusterFilter::new(&options.filter)?, ..Default::default() })
+    }
+
+    fn push_text(&mut self, text: &str) -> Result<()> {
+        for segment in iter_grapheme_icu4x(text)? {
+            let (bytes, codepoints_len, utf16_len) =
+                (segment.len(), segment.chars().count(), segment.encode_utf16().count());
+            if !self.filter.matches(&segment) {
+                self.advance(bytes, codepoints_len, utf16_len);
+                continue;
+            }
+            let codepoints = segment
+                .chars()
+                .enumerate()
+                .map(|(index, ch)| build_codepoint_info(index, ch, self.unihan))
+                .collect();
+            let emoji = classify_emoji(&segment);
+            self.push(segment, codepoints, emoji, None);
+            self.advance(bytes, codepoints_len, utf16_len);
+        }
+        Ok(())
+    }
+
+    fn push_invalid(&mut self, sequence: &InvalidSequence) {
+        // An invalid sequence decodes to a single U+FFFD, like String::from_utf8_lossy
+        if !self.filter.matches_invalid() {
+            self.advance(sequence.bytes.len(), 1, 1);
+            return;
+        }
+        let display = sequence
+            .bytes
+            .iter()
+            .map(|byte| format!("\\x{:02X}", byte))
+            .collect::<String>();
+        let invalid = InvalidSequenceInfo {
+            bytes: format_hex_bytes(&sequence.bytes),
+            kind: sequence.kind,
+            reason: sequence.kind.description().to_string(),
+        };
+        self.push(display, Vec::new(), None, Some(invalid));
+        self.advance(sequence.bytes.len(), 1, 1);
+    }
+
+    fn push(
+        &mut self,
+        display: String,
+        codepoints: Vec<CodepointInfo>,
+        emoji: Option<EmojiInfo>,
+        invalid: Option<InvalidSequenceInfo>,
+    ) {
+        self.clusters.push(ClusterInfo {
+            cluster_index: self.cluster_count,
+            display,
+            byte_offset: self.byte_offset,
+            codepoint_offset: self.codepoint_offset,
+            utf16_offset: self.utf16_offset,
+            codepoints,
+            emoji,
+            invalid,
+        });
+    }
+
+    fn advance(&mut self, bytes: usize, codepoints: usize, utf16_units: usize) {
+        self.cluster_count += 1;
+        self.byte_offset += bytes;
+        self.codepoint_offset += codepoints;
+        self.utf16_offset += utf16_units;
+    }
+}
+
+fn build_codepoint_info(index: usize, ch: char, unihan: bool) -> CodepointInfo {
+    CodepointInfo {
+        index,
+        char: if is_invisible(ch) {
+            format!("\\u{:04x}", ch as u32)
+        } else {
+            ch.to_string()
+        },
+        codepoint: format!("U+{:04X}", ch as u32),
+        name: get_unicode_name(ch),
+        label: get_codepoint_label(ch as u32),
+        aliases: get_name_aliases(ch),
+        utf8: encode_utf8_hex(ch),
+        utf16: encode_utf16_hex(ch),
+        special: classify_special_char(ch),
+        unihan: if unihan { get_unihan_info(ch) } else { None },
+        properties: get_char_properties(ch),
+    }
+}
+
+fn format_hex_bytes(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn encode_utf8_hex(ch: char) -> String {
+    let mut buf = [0u8; 4];
+    format_hex_bytes(ch.encode_utf8(&mut buf).as_bytes())
+}
+
+fn encode_utf16_hex(ch: char) -> String {
+    let mut buf = [0u16; 2];
+    ch.encode_utf16(&mut buf)
+        .iter()
+        .map(|unit| format!("{:04X}", unit))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn classify_special_char(ch: char) -> Option<SpecialCharKind> {
+    let general_category = maps::general_category().get(ch);
+    if sets::bidi_control().contains(ch) {
+        Some(SpecialCharKind::BidiControl)
+    } else if sets::join_control().contains(ch) {
+        Some(SpecialCharKind::Joiner)
+    } else if sets::variation_selector().contains(ch) {
+        Some(SpecialCharKind::VariationSelector)
+    } else if general_category == GeneralCategory::Control {
+        Some(SpecialCharKind::Control)
+    } else if matches!(
+        general_category,
+        GeneralCategory::NonspacingMark | GeneralCategory::SpacingMark | GeneralCategory::EnclosingMark
+    ) {
+        Some(SpecialCharKind::CombiningMark)
+    } else if ch != ' ' && sets::white_space().contains(ch) {
+        Some(SpecialCharKind::Whitespace)
+    } else if general_category == GeneralCategory::PrivateUse {
+        Some(SpecialCharKind::PrivateUse)
+    } else {
+        None
+    }
+}
+
+pub fn classify_emoji(cluster: &str) -> Option<EmojiInfo> {
+    let chars: Vec<char> = cluster.chars().collect();
+    let first = *chars.first()?;
+    let emoji_count = chars
+        .iter()
+        .filter(|&&ch| !ch.is_ascii() && sets::emoji().contains(ch))
+        .count();
+
+    let sequence_type = if chars.contains(&'\u{200D}') && emoji_count >= 2 {
+        EmojiSequenceType::Zwj
+    } else if chars.len() == 2 && chars.iter().all(|&ch| sets::regional_indicator().contains(ch)) {
+        EmojiSequenceType::Flag
+    } else if sets::emoji().contains(first) && chars[1..].iter().any(|&ch| ('\u{E0020}'..='\u{E007F}').contains(&ch)) {
+        EmojiSequenceType::Tag
+    } else if matches!(first, '0'..='9' | '#' | '*') && chars.len() > 1 && chars.last() == Some(&'\u{20E3}') {
+        EmojiSequenceType::Keycap
+    } else if chars.len() > 1
+        && sets::emoji_modifier_base().contains(first)
+        && sets::emoji_modifier().contains(chars[1])
+    {
+        EmojiSequenceType::Modifier
+    } else if sets::emoji().contains(first)
+        && (!first.is_ascii() || chars.contains(&'\u{FE0F}'))
+        && chars[1..].iter().all(|&ch| matches!(ch, '\u{FE0E}' | '\u{FE0F}'))
+    {
+        EmojiSequenceType::Basic
+    } else {
+        return None;
+    };
+
+    // Text-style variants are not in the emoji list; look up the name without the selector
+    let emoji = emojis::get(cluster).or_else(|| emojis::get(&cluster.replace('\u{FE0E}', "")));
+    let emoji_presentation = if chars.contains(&'\u{FE0E}') {
+        false
+    } else if chars.contains(&'\u{FE0F}') || sequence_type != EmojiSequenceType::Basic {
+        true
+    } else {
+        sets::emoji_presentation().contains(first)
+    };
+
+    Some(EmojiInfo {
+        sequence_type,
+        name: emoji.map(|emoji| emoji.name().to_string()),
+        rgi: emoji.is_some_and(|emoji| emoji.as_str() == cluster),
+        emoji_presentation,
+    })
+}
+
+fn special_char_color(kind: SpecialCharKind) -> &'static str {
+    match kind {
+        SpecialCharKind::Control => "\x1b[31m",
+        SpecialCharKind::Joiner => "\x1b[35m",
+        SpecialCharKind::VariationSelector => "\x1b[36m",
+        SpecialCharKind::BidiControl => "\x1b[97;41m",
+        SpecialCharKind::CombiningMark => "\x1b[32m",
+        SpecialCharKind::Whitespace => "\x1b[34m",
+        SpecialCharKind::PrivateUse => "\x1b[33m",
     }
 }
 
-fn format_text_output(clusters: &[ClusterInfo]) -> Result<String> {
+fn format_text_output(clusters: &[ClusterInfo], color: bool) -> Result<String> {
     let mut output = String::new();
     
     for cluster in clusters {
-        output.push_str(&format!("\nCluster {}: {} ({} codepoint{})\n", 
+        if let Some(invalid) = &cluster.invalid {
+            output.push_str(&format!("\nCluster {}: {} (invalid)  offset: byte {}, codepoint {}, utf-16 {}\n",
+                cluster.cluster_index,
+                cluster.display,
+                cluster.byte_offset,
+                cluster.codepoint_offset,
+                cluster.utf16_offset
+            ));
+            let line = format!("bytes: {}  reason: {}", invalid.bytes, invalid.reason);
+            if color {
+                output.push_str(&format!("  \x1b[1;31m{}\x1b[0m\n", line));
+            } else {
+                output.push_str(&format!("  {}\n", line));
+            }
+            continue;
+        }
+
+        output.push_str(&format!("\nCluster {}: {} ({} codepoint{})  offset: byte {}, codepoint {}, utf-16 {}\n", 
             cluster.cluster_index, 
             cluster.display, 
             cluster.codepoints.len(),
-            if cluster.codepoints.len() == 1 { "" } else { "s" }
+            if cluster.codepoints.len() == 1 { "" } else { "s" },
+            cluster.byte_offset,
+            cluster.codepoint_offset,
+            cluster.utf16_offset
         ));
+        if let Some(emoji) = &cluster.emoji {
+            output.push_str(&format_emoji_text(emoji));
+        }
         
         for codepoint in &cluster.codepoints {
-            output.push_str(&format!("  [{}] {}    {}  {}\n", 
-                codepoint.index, 
-                codepoint.char,
-                codepoint.codepoint,
-                codepoint.name
-            ));
+            let line = format_codepoint_line(codepoint);
+            match codepoint.special {
+                Some(kind) if color => {
+                    output.push_str(&format!("  {}{}\x1b[0m\n", special_char_color(kind), line));
+                }
+                _ => output.push_str(&format!("  {}\n", line)),
+            }
+            output.push_str(&format!("      utf-8: {}  utf-16: {}\n", codepoint.utf8, codepoint.utf16));
+            output.push_str(&format_properties_text(&codepoint.properties));
+            if let Some(unihan) = &codepoint.unihan {
+                output.push_str(&format_unihan_text(unihan));
+            }
         }
     }
     
@@ -175,8 +762,78 @@ fn format_text_output(clusters: &[ClusterInfo]) -> Result<String> {
     Ok(output)
 }
 
+fn format_codepoint_line(codepoint: &CodepointInfo) -> String {
+    let mut line = format!("[{}] {}    {}  {}", 
+        codepoint.index, 
+        codepoint.char,
+        codepoint.codepoint,
+        codepoint.name
+    );
+    if !codepoint.aliases.is_empty() {
+        line.push_str(&format!("  (alias: {})", codepoint.aliases.join(", ")));
+    }
+    line
+}
+
+fn format_unihan_text(unihan: &UnihanInfo) -> String {
+    format!("      unihan: mandarin: {}  jis: {}  shift_jis: {}\n",
+        unihan.mandarin.as_deref().unwrap_or("-"),
+        unihan.jis_x0208.as_deref().unwrap_or("-"),
+        unihan.shift_jis.as_deref().unwrap_or("-")
+    )
+}
+
+fn format_emoji_text(emoji: &EmojiInfo) -> String {
+    format!("  emoji: {}  name: {}  rgi: {}  presentation: {}\n",
+        emoji.sequence_type.description(),
+        emoji.name.as_deref().unwrap_or("-"),
+        if emoji.rgi { "yes" } else { "no" },
+        if emoji.emoji_presentation { "emoji" } else { "text" }
+    )
+}
+
+fn format_properties_text(properties: &CharProperties) -> String {
+    let mut script = properties.script.clone();
+    if properties.script_extensions != std::slice::from_ref(&properties.script) {
+        script.push_str(&format!(" (ext: {})", properties.script_extensions.join(" ")));
+    }
+
+    let mut output = format!("      category: {}  script: {}  block: {}\n",
+        properties.general_category,
+        script,
+        properties.block
+    );
+    output.push_str(&format!("      bidi: {}  width: {}  ccc: {}  gcb: {}  age: {}",
+        properties.bidi_class,
+        properties.east_asian_width,
+        properties.canonical_combining_class,
+        properties.grapheme_cluster_break,
+        properties.age.as_deref().unwrap_or("unassigned")
+    ));
+    if let Some(numeric_value) = &properties.numeric_value {
+        output.push_str(&format!("  numeric: {}", numeric_value));
+    }
+    output.push('\n');
+    output
+}
+
+// Every cluster carries the schema version, so a single JSONL line is still self-describing
+#[derive(Serialize)]
+struct DumpCluster<'a> {
+    #[serde(flatten)]
+    cluster: &'a ClusterInfo,
+    schema_version: u32,
+}
+
+impl<'a> DumpCluster<'a> {
+    fn new(cluster: &'a ClusterInfo) -> Self {
+        DumpCluster { cluster, schema_version: DUMP_SCHEMA_VERSION }
+    }
+}
+
 fn format_json_output(clusters: &[ClusterInfo]) -> Result<String> {
-    let json = serde_json::to_string_pretty(clusters)?;
+    let clusters: Vec<DumpCluster> = clusters.iter().map(DumpCluster::new).collect();
+    let json = serde_json::to_string_pretty(&clusters)?;
     Ok(json)
 }
 
@@ -184,7 +841,7 @@ fn format_jsonl_output(clusters: &[ClusterInfo]) -> Result<String> {
     let mut output = String::new();
     
     for cluster in clusters {
-        let line = serde_json::to_string(cluster)?;
+        let line = serde_json::to_string(&DumpCluster::new(cluster))?;
         output.push_str(&line);
         output.push('\n');
     }
@@ -192,632 +849,2992 @@ fn format_jsonl_output(clusters: &[ClusterInfo]) -> Result<String> {
     Ok(output)
 }
 
-pub fn ord_characters(input: &str, lowercase: bool, no_prefix: bool) -> Vec<String> {
-    input.chars().map(|ch| {
-        let codepoint = ch as u32;
-        if no_prefix {
-            if lowercase {
-                format!("{:x}", codepoint)
-            } else {
-                format!("{:X}", codepoint)
-            }
-        } else {
-            if lowercase {
-                format!("0x{:x}", codepoint)
+const DELIMITED_COLUMNS: &[&str] = &[
+    "cluster_index",
+    "codepoint_index",
+    "cluster",
+    "char",
+    "codepoint",
+    "name",
+    "utf8",
+    "utf16",
+    "general_category",
+    "script",
+    "script_extensions",
+    "block",
+    "bidi_class",
+    "east_asian_width",
+    "canonical_combining_class",
+    "grapheme_cluster_break",
+    "age",
+    "numeric_value",
+    "label",
+    "aliases",
+];
+
+fn format_delimited_output(clusters: &[ClusterInfo], delimiter: char, header: bool) -> Result<String> {
+    let mut output = String::new();
+
+    if header {
+        push_delimited_row(&mut output, DELIMITED_COLUMNS.iter().map(|column| column.to_string()), delimiter);
+    }
+
+    for cluster in clusters {
+        if let Some(invalid) = &cluster.invalid {
+            let mut row = vec![
+                cluster.cluster_index.to_string(),
+                String::new(),
+                cluster.display.clone(),
+                String::new(),
+                String::new(),
+                format!("<invalid: {}>", invalid.reason),
+                invalid.bytes.clone(),
+            ];
+            row.resize(DELIMITED_COLUMNS.len(), String::new());
+            push_delimited_row(&mut output, row.into_iter(), delimiter);
+            continue;
+        }
+
+        for codepoint in &cluster.codepoints {
+            let properties = &codepoint.properties;
+            let row = vec![
+                cluster.cluster_index.to_string(),
+                codepoint.index.to_string(),
+                cluster.display.clone(),
+                codepoint.char.clone(),
+                codepoint.codepoint.clone(),
+                codepoint.name.clone(),
+                codepoint.utf8.clone(),
+                codepoint.utf16.clone(),
+                properties.general_category.clone(),
+                properties.script.clone(),
+                properties.script_extensions.join(" "),
+                properties.block.clone(),
+                properties.bidi_class.clone(),
+                properties.east_asian_width.clone(),
+                properties.canonical_combining_class.to_string(),
+                properties.grapheme_cluster_break.clone(),
+                properties.age.clone().unwrap_or_default(),
+                properties.numeric_value.clone().unwrap_or_default(),
+                codepoint.label.map(|label| label.prefix().to_string()).unwrap_or_default(),
+                codepoint.aliases.join("; "),
+            ];
+            push_delimited_row(&mut output, row.into_iter(), delimiter);
+        }
+    }
+
+    Ok(output)
+}
+
+fn push_delimited_row(output: &mut String, fields: impl Iterator<Item = String>, delimiter: char) {
+    let fields: Vec<String> = fields
+        .map(|field| {
+            if delimiter == '\t' {
+                escape_tsv_field(&field)
             } else {
-                format!("0x{:X}", codepoint)
+                quote_csv_field(&field)
             }
-        }
-    }).collect()
+        })
+        .collect();
+    output.push_str(&fields.join(&delimiter.to_string()));
+    output.push('\n');
 }
 
-pub fn chr_from_codepoints(codepoints: &[String]) -> Result<String> {
-    let mut result = String::new();
-    
-    for cp_str in codepoints {
-        let hex_str = cp_str.strip_prefix("0x")
-            .or_else(|| cp_str.strip_prefix("0X"))
-            .unwrap_or(cp_str);
-            
-        let codepoint = u32::from_str_radix(hex_str, 16)
-            .map_err(|_| anyhow::anyhow!("Invalid hex format: {}", cp_str))?;
-            
-        let ch = char::from_u32(codepoint)
-            .ok_or_else(|| anyhow::anyhow!("Invalid Unicode codepoint: U+{:X}", codepoint))?;
-            
-        result.push(ch);
+fn quote_csv_field(field: &str) -> String {
+    // RFC 4180: quote fields containing separators, quotes or line breaks
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
     }
-    
-    Ok(result)
 }
 
-#[derive(Debug, Clone)]
-pub enum HexFormat {
-    Default,
-    Spaced,
-    Escaped,
+fn escape_tsv_field(field: &str) -> String {
+    // TSV has no quoting, so tabs and line breaks use the same \uXXXX form as the char column
+    field
+        .chars()
+        .map(|ch| match ch {
+            '\t' | '\r' | '\n' => format!("\\u{:04x}", ch as u32),
+            _ => ch.to_string(),
+        })
+        .collect()
 }
 
-pub fn bin2hex(input: &str, lowercase: bool, format: HexFormat) -> Result<String> {
-    let bytes = input.as_bytes();
-    let hex_chars: Vec<String> = bytes.iter().map(|byte| {
-        if lowercase {
-            format!("{:02x}", byte)
-        } else {
-            format!("{:02X}", byte)
-        }
-    }).collect();
+pub fn is_invisible(ch: char) -> bool {
+    let general_category = maps::general_category().get(ch);
+    ch != ' '
+        && (matches!(
+            general_category,
+            GeneralCategory::Control
+                | GeneralCategory::Format
+                | GeneralCategory::SpaceSeparator
+                | GeneralCategory::LineSeparator
+                | GeneralCategory::ParagraphSeparator
+        ) || sets::default_ignorable_code_point().contains(ch))
+}
 
-    match format {
-        HexFormat::Default => Ok(hex_chars.join("")),
-        HexFormat::Spaced => Ok(hex_chars.join(" ")),
-        HexFormat::Escaped => Ok(hex_chars.iter().map(|h| format!("\\x{}", h)).collect::<Vec<_>>().join("")),
-    }
+fn visible_text(input: &str) -> String {
+    input
+        .chars()
+        .map(|ch| {
+            if is_invisible(ch) {
+                format!("\\u{:04x}", ch as u32)
+            } else {
+                ch.to_string()
+            }
+        })
+        .collect()
 }
 
-pub fn hex2bin(hex_input: &str) -> Result<String> {
-    let cleaned_input = if hex_input.starts_with("\\x") {
-        // Handle escaped format: \xF0\x9F\x8D\xA3
-        hex_input.replace("\\x", "")
-    } else if hex_input.contains(' ') {
-        // Handle spaced format: F0 9F 8D A3
-        hex_input.replace(' ', "")
+fn markdown_code(text: &str) -> String {
+    let text = visible_text(text).replace('|', "\\|");
+    if text.contains('`') {
+        format!("`` {} ``", text)
     } else {
-        // Handle default format: F09F8DA3
-        hex_input.to_string()
-    };
-
-    if cleaned_input.len() % 2 != 0 {
-        return Err(anyhow::anyhow!("Invalid hex input: odd number of characters"));
+        format!("`{}`", text)
     }
+}
 
-    let mut bytes = Vec::new();
-    for i in (0..cleaned_input.len()).step_by(2) {
-        let hex_pair = &cleaned_input[i..i+2];
-        let byte = u8::from_str_radix(hex_pair, 16)
-            .map_err(|_| anyhow::anyhow!("Invalid hex character in: {}", hex_pair))?;
-        bytes.push(byte);
+fn markdown_text(text: &str) -> String {
+    let mut escaped = String::new();
+    for ch in text.chars() {
+        if matches!(ch, '\\' | '|' | '<' | '>') {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
     }
-
-    let result = String::from_utf8(bytes)
-        .map_err(|_| anyhow::anyhow!("Invalid UTF-8 sequence"))?;
-    
-    Ok(result)
+    escaped
 }
 
-#[derive(Debug, Clone)]
-pub enum InputFormat {
-    Binary,
-    Hex,
-}
+fn format_markdown_output(clusters: &[ClusterInfo]) -> Result<String> {
+    let mut output = String::new();
 
-pub fn scrub_invalid_utf8(input: &str, format: InputFormat) -> Result<String> {
-    let bytes = match format {
-        InputFormat::Binary => {
-            // Treat input as binary data (UTF-8 bytes)
-            input.as_bytes().to_vec()
+    output.push_str("| Cluster | Grapheme | Index | Char | Code point | UTF-8 | Name | Category | Script |\n");
+    output.push_str("|---:|---|---:|---|---|---|---|---|---|\n");
+
+    for cluster in clusters {
+        if let Some(invalid) = &cluster.invalid {
+            output.push_str(&format!("| {} | {} |  |  |  | {} | {} |  |  |\n",
+                cluster.cluster_index,
+                markdown_code(&cluster.display),
+                invalid.bytes,
+                markdown_text(&format!("<invalid: {}>", invalid.reason))
+            ));
+            continue;
         }
-        InputFormat::Hex => {
-            // Parse as hexadecimal string (similar to hex2bin)
-            let cleaned_input = if input.starts_with("\\x") {
-                // Handle escaped format: \xF0\x9F\x8D\xA3
-                input.replace("\\x", "")
-            } else if input.contains(' ') {
-                // Handle spaced format: F0 9F 8D A3
-                input.replace(' ', "")
+
+        for codepoint in &cluster.codepoints {
+            let (cluster_index, grapheme) = if codepoint.index == 0 {
+                (cluster.cluster_index.to_string(), markdown_code(&cluster.display))
             } else {
-                // Handle default format: F09F8DA3
-                input.to_string()
+                (String::new(), String::new())
             };
-
-            if cleaned_input.is_empty() {
-                return Ok(String::new());
-            }
-
-            if cleaned_input.len() % 2 != 0 {
-                return Err(anyhow::anyhow!("Invalid hex input: odd number of characters"));
-            }
-
-            let mut bytes = Vec::new();
-            for i in (0..cleaned_input.len()).step_by(2) {
-                let hex_pair = &cleaned_input[i..i+2];
-                let byte = u8::from_str_radix(hex_pair, 16)
-                    .map_err(|_| anyhow::anyhow!("Invalid hex character in: {}", hex_pair))?;
-                bytes.push(byte);
-            }
-            bytes
+            output.push_str(&format!("| {} | {} | {} | {} | {} | {} | {} | {} | {} |\n",
+                cluster_index,
+                grapheme,
+                codepoint.index,
+                markdown_code(&codepoint.char),
+                codepoint.codepoint,
+                codepoint.utf8,
+                markdown_text(&codepoint.name),
+                codepoint.properties.general_category,
+                codepoint.properties.script
+            ));
         }
-    };
+    }
 
-    // Use from_utf8_lossy to replace invalid UTF-8 sequences with U+FFFD
-    let result = String::from_utf8_lossy(&bytes).into_owned();
-    Ok(result)
+    Ok(output)
 }
 
-#[derive(Debug, Clone)]
-pub enum EscapeFormat {
-    Default,
-    Json,
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::new();
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
 }
 
-pub fn escape_unicode(input: &str) -> String {
-    escape_unicode_with_format(input, EscapeFormat::Default)
+fn text_direction(input: &str) -> &'static str {
+    // Direction of the first strong character, as in the HTML dir=auto heuristic
+    for ch in input.chars() {
+        match maps::bidi_class().get(ch) {
+            BidiClass::LeftToRight => return "ltr",
+            BidiClass::RightToLeft | BidiClass::ArabicLetter => return "rtl",
+            _ => {}
+        }
+    }
+    "auto"
 }
 
-pub fn escape_unicode_with_format(input: &str, format: EscapeFormat) -> String {
-    match format {
-        EscapeFormat::Default => {
-            input.chars()
-                .map(|ch| format!("\\u{{{:X}}}", ch as u32))
-                .collect::<Vec<_>>()
-                .join("")
+fn format_html_output(clusters: &[ClusterInfo], input: &str, lang: Option<&str>) -> Result<String> {
+    let mut output = String::new();
+
+    output.push_str(&format!("<table class=\"mojibox-dump\" lang=\"{}\" dir=\"{}\">\n",
+        escape_html(lang.unwrap_or("und")),
+        text_direction(input)
+    ));
+    output.push_str("  <thead>\n");
+    output.push_str("    <tr><th>Cluster</th><th>Grapheme</th><th>Index</th><th>Char</th><th>Code point</th><th>UTF-8</th><th>Name</th><th>Category</th><th>Script</th></tr>\n");
+    output.push_str("  </thead>\n");
+    output.push_str("  <tbody>\n");
+
+    for cluster in clusters {
+        if let Some(invalid) = &cluster.invalid {
+            output.push_str(&format!("    <tr class=\"invalid\"><td>{}</td><td><code>{}</code></td><td></td><td></td><td></td><td>{}</td><td>{}</td><td></td><td></td></tr>\n",
+                cluster.cluster_index,
+                escape_html(&cluster.display),
+                invalid.bytes,
+                escape_html(&format!("<invalid: {}>", invalid.reason))
+            ));
+            continue;
         }
-        EscapeFormat::Json => {
-            input.chars()
-                .flat_map(|ch| {
-                    let code_point = ch as u32;
-                    if code_point <= 0xFFFF {
-                        // BMP character - single \uXXXX
-                        vec![format!("\\u{:04X}", code_point)]
-                    } else {
-                        // Supplementary character - surrogate pair
-                        let adjusted = code_point - 0x10000;
-                        let high = 0xD800 + (adjusted >> 10);
-                        let low = 0xDC00 + (adjusted & 0x3FF);
-                        vec![format!("\\u{:04X}", high), format!("\\u{:04X}", low)]
-                    }
+
+        for codepoint in &cluster.codepoints {
+            output.push_str("    <tr>");
+            if codepoint.index == 0 {
+                let rowspan = cluster.codepoints.len();
+                output.push_str(&format!("<td rowspan=\"{}\">{}</td><td rowspan=\"{}\" dir=\"auto\"><code>{}</code></td>",
+                    rowspan,
+                    cluster.cluster_index,
+                    rowspan,
+                    escape_html(&visible_text(&cluster.display))
+                ));
+            }
+            output.push_str(&format!("<td>{}</td><td dir=\"auto\"><code>{}</code></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                codepoint.index,
+                escape_html(&visible_text(&codepoint.char)),
+                codepoint.codepoint,
+                codepoint.utf8,
+                escape_html(&codepoint.name),
+                codepoint.properties.general_category,
+                codepoint.properties.script
+            ));
+        }
+    }
+
+    output.push_str("  </tbody>\n");
+    output.push_str("</table>\n");
+
+    Ok(output)
+}
+
+// Version of the dump JSON/JSONL format accepted by undump. Adding fields does not change
+// the version; renaming, removing or changing the meaning of a field does.
+pub const DUMP_SCHEMA_VERSION: u32 = 1;
+pub const DUMP_SCHEMA: &str = include_str!("../schema/dump.v1.schema.json");
+
+pub fn undump(input: &str) -> Result<String> {
+    String::from_utf8(undump_bytes(input)?)
+        .map_err(|_| anyhow::anyhow!("Dump contains invalid UTF-8 sequences; use undump_bytes to rebuild raw bytes"))
+}
+
+pub fn undump_bytes(input: &str) -> Result<Vec<u8>> {
+    let clusters = parse_dump(input)?;
+    let mut output = Vec::new();
+    let mut mismatches = Vec::new();
+
+    for (position, cluster) in clusters.iter().enumerate() {
+        if let Some(version) = cluster.schema_version.filter(|&version| version != DUMP_SCHEMA_VERSION) {
+            return Err(anyhow::anyhow!("Cluster {}: dump schema version {} is not supported (expected {})",
+                position, version, DUMP_SCHEMA_VERSION));
+        }
+        // A filtered dump leaves out clusters, so the string can't be rebuilt from it
+        let index = cluster.cluster_index.unwrap_or(position);
+        if index != position {
+            return Err(anyhow::anyhow!("Cluster {} is at position {}: cluster indices must run from 0 without gaps \
+                (a filtered dump can't be undumped)", index, position));
+        }
+        if let Some(invalid) = &cluster.invalid {
+            let bytes = input_to_bytes(&invalid.bytes, InputFormat::Hex)
+                .map_err(|e| anyhow::anyhow!("Cluster {}: invalid bytes field: {}", index, e))?;
+            let display: String = bytes.iter().map(|byte| format!("\\x{:02X}", byte)).collect();
+            if cluster.display != display {
+                mismatches.push(format!("cluster {}: display {:?} does not match bytes {}", index, cluster.display, invalid.bytes));
+            }
+            output.extend_from_slice(&bytes);
+            continue;
+        }
+
+        let mut text = String::new();
+        for codepoint in &cluster.codepoints {
+            let ch = codepoint
+                .codepoint
+                .strip_prefix("U+")
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .and_then(char::from_u32)
+                .ok_or_else(|| anyhow::anyhow!("Cluster {}: invalid code point: {}", index, codepoint.codepoint))?;
+            if let Some(char) = codepoint.char.as_ref().filter(|&char| *char != ch.to_string() && *char != format!("\\u{:04x}", ch as u32)) {
+                mismatches.push(format!("cluster {}: char {:?} does not match {}", index, char, codepoint.codepoint));
+            }
+            text.push(ch);
+        }
+        if cluster.display != text {
+            let codepoints: Vec<&str> = cluster.codepoints.iter().map(|codepoint| codepoint.codepoint.as_str()).collect();
+            if codepoints.is_empty() {
+                mismatches.push(format!("cluster {}: display {:?} has no code points", index, cluster.display));
+            } else {
+                mismatches.push(format!("cluster {}: display {:?} does not match code points {} ({:?})",
+                    index, cluster.display, codepoints.join(" "), text));
+            }
+        }
+        output.extend_from_slice(text.as_bytes());
+    }
+
+    if !mismatches.is_empty() {
+        return Err(anyhow::anyhow!("Dump does not match its code points:\n  {}", mismatches.join("\n  ")));
+    }
+    Ok(output)
+}
+
+// Only the fields undump rebuilds from or checks against. Everything else in a dump is
+// derived from the code points, so hand-edited fixtures can leave it out.
+#[derive(Deserialize)]
+struct UndumpCluster {
+    schema_version: Option<u32>,
+    cluster_index: Option<usize>,
+    display: String,
+    #[serde(default)]
+    codepoints: Vec<UndumpCodepoint>,
+    invalid: Option<UndumpInvalid>,
+}
+
+#[derive(Deserialize)]
+struct UndumpCodepoint {
+    codepoint: String,
+    char: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct UndumpInvalid {
+    bytes: String,
+}
+
+// Accepts both `dump --format json` (an array) and `dump --format jsonl` (one cluster per line)
+fn parse_dump(input: &str) -> Result<Vec<UndumpCluster>> {
+    if input.trim_start().starts_with('[') {
+        return serde_json::from_str(input).map_err(|e| anyhow::anyhow!("Invalid dump JSON: {}", e));
+    }
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line).map_err(|e| anyhow::anyhow!("Invalid dump JSON on line {}: {}", i + 1, e))
+        })
+        .collect()
+}
+
+pub fn lookup_char_by_name(name: &str) -> Option<char> {
+    unicode_names2::character(name).or_else(|| {
+        let name = name.to_ascii_uppercase();
+        let names = ucd_tables::NAMES.iter().find(|&&(_, new_name)| new_name == name).map(|&(codepoint, _)| codepoint);
+        let ideograph = ["CJK UNIFIED IDEOGRAPH-", "TANGUT IDEOGRAPH-"]
+            .iter()
+            .find_map(|prefix| name.strip_prefix(prefix))
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .filter(|&codepoint| char::from_u32(codepoint).and_then(character_name).as_deref() == Some(name.as_str()));
+        names.or(ideograph).or_else(|| {
+            ucd_tables::NAME_ALIASES
+                .iter()
+                .find(|(_, aliases)| aliases.contains(&name.as_str()))
+                .map(|&(codepoint, _)| codepoint)
+        })
+        .and_then(char::from_u32)
+    })
+}
+
+// Exact name or alias first; otherwise a wildcard search if the query has `*` or `?`,
+// and a substring search if it does not
+pub fn find_chars_by_name(query: &str) -> Vec<char> {
+    if let Some(ch) = lookup_char_by_name(query) {
+        return vec![ch];
+    }
+    let pattern = query.to_ascii_uppercase();
+    let wildcard = pattern.contains(['*', '?']);
+    named_chars()
+        .filter(|(_, names)| {
+            names.iter().any(|name| {
+                if wildcard {
+                    wildcard_match(pattern.as_bytes(), name.as_bytes())
+                } else {
+                    name.contains(&pattern)
+                }
+            })
+        })
+        .map(|(ch, _)| ch)
+        .collect()
+}
+
+// Ranked by Jaro-Winkler similarity of the best matching name or alias
+pub fn fuzzy_find_chars_by_name(query: &str, limit: usize) -> Vec<char> {
+    const MIN_SIMILARITY: f64 = 0.8;
+    let query = query.to_ascii_uppercase();
+    let mut matches: Vec<(f64, char)> = named_chars()
+        .filter_map(|(ch, names)| {
+            let score = names
+                .iter()
+                .map(|name| strsim::jaro_winkler(&query, name))
+                .fold(0.0, f64::max);
+            (score >= MIN_SIMILARITY).then_some((score, ch))
+        })
+        .collect();
+    matches.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+    matches.into_iter().take(limit).map(|(_, ch)| ch).collect()
+}
+
+pub fn format_codepoint_lines(chars: &[char]) -> String {
+    chars
+        .iter()
+        .enumerate()
+        .map(|(index, &ch)| format!("{}\n", format_codepoint_line(&build_codepoint_info(index, ch, false))))
+        .collect()
+}
+
+fn named_chars() -> impl Iterator<Item = (char, Vec<String>)> {
+    ('\0'..=char::MAX).filter_map(|ch| {
+        let mut names = get_name_aliases(ch);
+        if let Some(name) = character_name(ch) {
+            names.insert(0, name);
+        }
+        (!names.is_empty()).then_some((ch, names))
+    })
+}
+
+fn wildcard_match(pattern: &[u8], text: &[u8]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == b'?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == b'*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            // Let the last `*` absorb one more character and retry
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&byte| byte == b'*')
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChartFormat {
+    Grid,
+    Lines,
+    Json,
+}
+
+// A block name ("Katakana", "halfwidth_and_fullwidth_forms") or a code point range
+// ("U+30A0..U+30FF", "30A0-30FF"), as an inclusive range
+pub fn resolve_chart_range(spec: &str) -> Result<(u32, u32)> {
+    if let Some((start, end)) = spec.split_once("..").or_else(|| spec.split_once('-')) {
+        if let (Ok(start), Ok(end)) = (parse_range_bound(start), parse_range_bound(end)) {
+            if start > end || end > char::MAX as u32 {
+                return Err(anyhow::anyhow!("Invalid code point range: {}", spec));
+            }
+            return Ok((start, end));
+        }
+    }
+    let key = loose_block_name(spec);
+    all_unicode_blocks()
+        .into_iter()
+        .find(|block| loose_block_name(block.name()) == key)
+        .map(|block| (block.start(), block.end()))
+        .ok_or_else(|| anyhow::anyhow!("Unknown block or code point range: {}", spec))
+}
+
+fn parse_range_bound(bound: &str) -> Result<u32> {
+    let bound = bound.trim();
+    let hex = ["U+", "u+", "0x", "0X"]
+        .iter()
+        .find_map(|prefix| bound.strip_prefix(prefix))
+        .unwrap_or(bound);
+    Ok(u32::from_str_radix(hex, 16)?)
+}
+
+// Block names match loosely, ignoring case, spaces, hyphens and underscores (UAX #44 LM3)
+fn loose_block_name(name: &str) -> String {
+    name.chars()
+        .filter(|ch| !matches!(ch, ' ' | '-' | '_'))
+        .map(|ch| ch.to_ascii_lowercase())
+        .collect()
+}
+
+fn all_unicode_blocks() -> Vec<unicode_blocks::UnicodeBlock> {
+    let mut blocks = Vec::new();
+    let mut codepoint = 0;
+    while codepoint <= char::MAX as u32 {
+        match char::from_u32(codepoint).and_then(unicode_blocks::find_unicode_block) {
+            Some(block) => {
+                blocks.push(block);
+                codepoint = block.end() + 1;
+            }
+            // Blocks always start at a multiple of 16
+            None => codepoint = (codepoint | 0xF) + 1,
+        }
+    }
+    blocks
+}
+
+// Assigned characters in the block or range, optionally limited to a general category
+pub fn chart_chars(spec: &str, category: Option<&str>) -> Result<Vec<char>> {
+    let (start, end) = resolve_chart_range(spec)?;
+    let category = category.map(parse_general_category).transpose()?;
+    let general_category = maps::general_category();
+    Ok((start..=end)
+        .filter_map(char::from_u32)
+        .filter(|&ch| general_category.get(ch) != GeneralCategory::Unassigned)
+        .filter(|&ch| category.is_none_or(|group| group.contains(general_category.get(ch))))
+        .collect())
+}
+
+pub fn format_chart(chars: &[char], format: ChartFormat) -> Result<String> {
+    match format {
+        ChartFormat::Grid => Ok(format_chart_grid(chars)),
+        ChartFormat::Lines => Ok(format_codepoint_lines(chars)),
+        ChartFormat::Json => {
+            let codepoints: Vec<CodepointInfo> = chars
+                .iter()
+                .enumerate()
+                .map(|(index, &ch)| build_codepoint_info(index, ch, false))
+                .collect();
+            Ok(format!("{}\n", serde_json::to_string_pretty(&codepoints)?))
+        }
+    }
+}
+
+// Rows of 16 code points like the Unicode code charts. Cells are padded by East Asian
+// Width so that columns line up in a terminal.
+fn format_chart_grid(chars: &[char]) -> String {
+    let mut output = format!("{:9}{}", "", (0..16).map(|column| format!("{:X}  ", column)).collect::<String>());
+    let mut row_start = None;
+    let mut next_column = 0;
+    for &ch in chars {
+        let start = ch as u32 & !0xF;
+        let column = (ch as u32 & 0xF) as usize;
+        if row_start != Some(start) {
+            output.push_str(&format!("\n{:9}", format!("U+{:04X}", start)));
+            row_start = Some(start);
+            next_column = 0;
+        }
+        output.push_str(&"   ".repeat(column - next_column));
+        output.push_str(&chart_cell(ch));
+        next_column = column + 1;
+    }
+    output.lines().map(|line| format!("{}\n", line.trim_end())).collect()
+}
+
+// Marks are shown on a dotted circle and invisible characters as a dotted circle alone
+fn chart_cell(ch: char) -> String {
+    let text = match maps::general_category().get(ch) {
+        GeneralCategory::NonspacingMark | GeneralCategory::EnclosingMark => format!("\u{25CC}{}", ch),
+        _ if ch == ' ' || is_invisible(ch) => "\u{25CC}".to_string(),
+        _ => ch.to_string(),
+    };
+    format!("{}{}", text, if is_wide(ch) { " " } else { "  " })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OrdNotation {
+    Hex,
+    Unicode,
+    Decimal,
+    Octal,
+    HtmlDecimal,
+    HtmlHex,
+    Css,
+    Escape,
+    Json,
+    Utf8,
+}
+
+pub fn ord_characters(input: &str, lowercase: bool, no_prefix: bool) -> Vec<String> {
+    ord_characters_with_notation(input, OrdNotation::Hex, lowercase, no_prefix)
+}
+
+// `lowercase` applies to every hex notation, `no_prefix` to the 0x and 0o prefixes
+pub fn ord_characters_with_notation(input: &str, notation: OrdNotation, lowercase: bool, no_prefix: bool) -> Vec<String> {
+    input.chars().map(|ch| {
+        let codepoint = ch as u32;
+        let hex = |value: u32, width: usize| {
+            if lowercase {
+                format!("{:0width$x}", value, width = width)
+            } else {
+                format!("{:0width$X}", value, width = width)
+            }
+        };
+        match notation {
+            OrdNotation::Hex if no_prefix => hex(codepoint, 0),
+            OrdNotation::Hex => format!("0x{}", hex(codepoint, 0)),
+            OrdNotation::Unicode => format!("U+{}", hex(codepoint, 4)),
+            OrdNotation::Decimal => codepoint.to_string(),
+            OrdNotation::Octal if no_prefix => format!("{:o}", codepoint),
+            OrdNotation::Octal => format!("0o{:o}", codepoint),
+            OrdNotation::HtmlDecimal => format!("&#{};", codepoint),
+            OrdNotation::HtmlHex => format!("&#x{};", hex(codepoint, 0)),
+            OrdNotation::Css => format!("\\{}", hex(codepoint, 0)),
+            OrdNotation::Escape => format!("\\u{{{}}}", hex(codepoint, 0)),
+            OrdNotation::Json => {
+                let mut units = [0u16; 2];
+                ch.encode_utf16(&mut units)
+                    .iter()
+                    .map(|&unit| format!("\\u{}", hex(unit as u32, 4)))
+                    .collect()
+            }
+            OrdNotation::Utf8 => {
+                let mut bytes = [0u8; 4];
+                let bytes = ch.encode_utf8(&mut bytes).bytes().map(|byte| hex(byte as u32, 2));
+                format!("[{}]", bytes.collect::<Vec<_>>().join(" "))
+            }
+        }
+    }).collect()
+}
+
+// One group per grapheme cluster: the cluster and the notation of each of its code points
+pub fn ord_graphemes(input: &str, notation: OrdNotation, lowercase: bool, no_prefix: bool) -> Result<Vec<(String, Vec<String>)>> {
+    Ok(iter_grapheme_icu4x(input)?
+        .into_iter()
+        .map(|cluster| {
+            let values = ord_characters_with_notation(&cluster, notation, lowercase, no_prefix);
+            (cluster, values)
+        })
+        .collect())
+}
+
+// For each character: its code point in binary, then for UTF-8 and UTF-16 the bit pattern,
+// the code point bits placed under the x's, the resulting bits and the encoded units
+pub fn explain_encoding(input: &str) -> String {
+    let mut output = String::new();
+    for (index, ch) in input.chars().enumerate() {
+        if index > 0 {
+            output.push('\n');
+        }
+        let codepoint = ch as u32;
+        let significant = format!("{:b}", codepoint);
+        // Significant bits in groups of four from the right
+        let digits: Vec<char> = significant.chars().collect();
+        let mut groups: Vec<String> = digits.rchunks(4).map(|group| group.iter().collect()).collect();
+        groups.reverse();
+        output.push_str(&format!("U+{:04X}  {}  {}\n", codepoint, visible_text(&ch.to_string()), get_unicode_name(ch)));
+        output.push_str(&format!("  {:<12}{} ({} bit{})\n", "code point", groups.join(" "), significant.len(), if significant.len() == 1 { "" } else { "s" }));
+
+        // UTF-8: the lead byte marks the length, continuation bytes carry 6 bits each
+        let mut utf8 = [0u8; 4];
+        let utf8 = ch.encode_utf8(&mut utf8).as_bytes();
+        let payload_bits: &[usize] = match utf8.len() {
+            1 => &[7],
+            2 => &[5, 6],
+            3 => &[4, 6, 6],
+            _ => &[3, 6, 6, 6],
+        };
+        let total: usize = payload_bits.iter().sum();
+        let bits = format!("{:0width$b}", codepoint, width = total);
+        let mut taken = 0;
+        let units: Vec<(String, String, String)> = utf8
+            .iter()
+            .zip(payload_bits)
+            .map(|(&byte, &width)| {
+                let marker = &format!("{:08b}", byte)[..8 - width];
+                let payload = &bits[taken..taken + width];
+                taken += width;
+                (format!("{}{}", marker, "x".repeat(width)), payload.to_string(), format!("{:08b}", byte))
+            })
+            .collect();
+        output.push_str(&format_explained_units("UTF-8", &units, utf8.iter().map(|byte| format!("{:02X}", byte))));
+
+        // UTF-16: code points above U+FFFF become a surrogate pair carrying 20 bits
+        let mut utf16 = [0u16; 2];
+        let utf16 = ch.encode_utf16(&mut utf16);
+        let units: Vec<(String, String, String)> = if utf16.len() == 1 {
+            vec![("x".repeat(16), format!("{:016b}", codepoint), format!("{:016b}", utf16[0]))]
+        } else {
+            let offset = codepoint - 0x10000;
+            output.push_str(&format!("  {:<12}U+{:04X} - U+10000 = {:05X} (20 bits)\n", "UTF-16", codepoint, offset));
+            let bits = format!("{:020b}", offset);
+            utf16
+                .iter()
+                .enumerate()
+                .map(|(index, &unit)| {
+                    let marker = if index == 0 { "110110" } else { "110111" };
+                    (format!("{}{}", marker, "x".repeat(10)), bits[index * 10..index * 10 + 10].to_string(), format!("{:016b}", unit))
                 })
-                .collect::<Vec<_>>()
-                .join("")
+                .collect()
+        };
+        let label = if utf16.len() == 1 { "UTF-16" } else { "" };
+        output.push_str(&format_explained_units(label, &units, utf16.iter().map(|unit| format!("{:04X}", unit))));
+    }
+    output
+}
+
+// Rows of pattern, payload (right-aligned under the x's), encoded bits and hex, one column per unit
+fn format_explained_units(label: &str, units: &[(String, String, String)], hex: impl Iterator<Item = String>) -> String {
+    let row = |cells: Vec<String>| cells.join(" ").trim_end().to_string();
+    let width = units[0].0.len();
+    let patterns = row(units.iter().map(|(pattern, _, _)| pattern.clone()).collect());
+    let payloads = row(units.iter().map(|(_, payload, _)| format!("{:>width$}", payload, width = width)).collect());
+    let encoded = row(units.iter().map(|(_, _, bits)| bits.clone()).collect());
+    let hex = row(hex.map(|hex| format!("{:<width$}", hex, width = width)).collect());
+    [patterns, payloads, encoded, hex]
+        .iter()
+        .enumerate()
+        .map(|(index, line)| format!("  {:<12}{}\n", if index == 0 { label } else { "" }, line))
+        .collect()
+}
+
+pub fn chr_from_codepoints(codepoints: &[String]) -> Result<String> {
+    chr_from_codepoints_with_options(codepoints, false)
+}
+
+// Each argument may hold several tokens separated by whitespace or commas. A token is a
+// single code point or a range ("U+3041..U+3096"), which expands to every character in
+// it; surrogates are always left out of ranges, unassigned code points on request
+pub fn chr_from_codepoints_with_options(codepoints: &[String], skip_unassigned: bool) -> Result<String> {
+    let mut result = String::new();
+    let tokens = codepoints
+        .iter()
+        .flat_map(|arg| arg.split(|ch: char| ch.is_whitespace() || ch == ','))
+        .filter(|token| !token.is_empty());
+
+    for (index, token) in tokens.enumerate() {
+        let position = index + 1;
+        let range = token.split_once("..").or_else(|| {
+            // "-" separates a range unless it is part of an entity or escape
+            token.split_once('-').filter(|(start, _)| !start.is_empty())
+        });
+        match range {
+            Some((start, end)) => {
+                let start = parse_codepoint_token(start)
+                    .map_err(|e| anyhow::anyhow!("{} at token {}: {}", e, position, token))?;
+                let end = parse_codepoint_token(end)
+                    .map_err(|e| anyhow::anyhow!("{} at token {}: {}", e, position, token))?;
+                if start > end {
+                    return Err(anyhow::anyhow!("Invalid range at token {}: {} (start is after end)", position, token));
+                }
+                let general_category = maps::general_category();
+                result.extend(
+                    (start..=end)
+                        .filter_map(char::from_u32)
+                        .filter(|&ch| !skip_unassigned || general_category.get(ch) != GeneralCategory::Unassigned),
+                );
+            }
+            None => {
+                let codepoint = parse_codepoint_token(token)
+                    .map_err(|e| anyhow::anyhow!("{} at token {}: {}", e, position, token))?;
+                let ch = char::from_u32(codepoint)
+                    .ok_or_else(|| anyhow::anyhow!("Surrogate code point U+{:04X} at token {}: {}", codepoint, position, token))?;
+                result.push(ch);
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+// U+3042, 0x3042, 3042 (hex), #12354 (decimal), &#12354;, &#x3042;, \u{3042} or \u3042.
+// Bare tokens are always hex, so lowercase hex such as "d7" is not taken for a decimal.
+fn parse_codepoint_token(token: &str) -> Result<u32> {
+    let (digits, radix) = if let Some(entity) = token.strip_prefix("&#").and_then(|t| t.strip_suffix(';')) {
+        match entity.strip_prefix('x').or_else(|| entity.strip_prefix('X')) {
+            Some(hex) => (hex, 16),
+            None => (entity, 10),
+        }
+    } else if let Some(escape) = token.strip_prefix("\\u{").and_then(|t| t.strip_suffix('}')) {
+        (escape, 16)
+    } else if let Some(escape) = token.strip_prefix("\\u").filter(|t| t.len() == 4) {
+        (escape, 16)
+    } else if let Some(decimal) = token.strip_prefix('#') {
+        (decimal, 10)
+    } else {
+        let hex = ["U+", "u+", "0x", "0X"]
+            .iter()
+            .find_map(|prefix| token.strip_prefix(prefix))
+            .unwrap_or(token);
+        (hex, 16)
+    };
+
+    // from_str_radix accepts a leading "+", which no notation here allows
+    if digits.is_empty() || !digits.chars().all(|ch| ch.is_digit(radix)) {
+        return Err(anyhow::anyhow!("Invalid code point"));
+    }
+    match u32::from_str_radix(digits, radix) {
+        Ok(codepoint) if codepoint <= char::MAX as u32 => Ok(codepoint),
+        _ => Err(anyhow::anyhow!("Code point out of range (above U+10FFFF)")),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum HexFormat {
+    Default,
+    Spaced,
+    Escaped,
+    CArray,
+    Rust,
+    Python,
+    JavaScript,
+    Go,
+    List,
+}
+
+pub fn bin2hex(input: &str, lowercase: bool, format: HexFormat) -> Result<String> {
+    Ok(bytes_to_hex(input.as_bytes(), lowercase, format, None))
+}
+
+// With a width, the output is split into lines of that many bytes; the language literals
+// then put each line on its own indented line, the way their formatters would
+pub fn bytes_to_hex(bytes: &[u8], lowercase: bool, format: HexFormat, width: Option<usize>) -> String {
+    let hex_chars: Vec<String> = bytes.iter().map(|byte| {
+        if lowercase {
+            format!("{:02x}", byte)
+        } else {
+            format!("{:02X}", byte)
+        }
+    }).collect();
+    let wrapped = width.is_some_and(|width| width > 0 && bytes.len() > width);
+    let lines: Vec<&[String]> = match width {
+        Some(width) if width > 0 && !bytes.is_empty() => hex_chars.chunks(width).collect(),
+        _ => vec![&hex_chars[..]],
+    };
+    let join_lines = |prefix: &str, separator: &str, line_end: &str| {
+        lines
+            .iter()
+            .map(|line| line.iter().map(|h| format!("{}{}", prefix, h)).collect::<Vec<_>>().join(separator))
+            .collect::<Vec<_>>()
+            .join(line_end)
+    };
+    let list = |open: &str, close: &str, indent: &str| {
+        if wrapped {
+            format!("{}\n{}{},\n{}", open, indent, join_lines("0x", ", ", &format!(",\n{}", indent)), close)
+        } else {
+            format!("{}{}{}", open, join_lines("0x", ", ", ""), close)
+        }
+    };
+
+    match format {
+        HexFormat::Default => join_lines("", "", "\n"),
+        HexFormat::Spaced => join_lines("", " ", "\n"),
+        HexFormat::Escaped => join_lines("\\x", "", "\n"),
+        HexFormat::List => join_lines("0x", ", ", ",\n"),
+        HexFormat::CArray => list("{", "}", "    "),
+        HexFormat::JavaScript => list("new Uint8Array([", "])", "    "),
+        HexFormat::Go => list("[]byte{", "}", "\t"),
+        // A backslash at the end of a line continues the literal without the line break
+        HexFormat::Rust => format!("b\"{}\"", join_lines("\\x", "", "\\\n  ")),
+        // Adjacent literals in parentheses are concatenated
+        HexFormat::Python if wrapped => format!("(\n    b\"{}\"\n)", join_lines("\\x", "", "\"\n    b\"")),
+        HexFormat::Python => format!("b\"{}\"", join_lines("\\x", "", "")),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HexParseMode {
+    Lenient,
+    Strict,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct HexParseError {
+    offset: usize,
+    token: String,
+    reason: &'static str,
+}
+
+// Lenient accepts any mix of 0x, \x and % prefixes and whitespace, comma, colon, semicolon,
+// quote and bracket separators, so every literal of bytes_to_hex parses as it is. Strict only
+// accepts continuous hex or \x escapes separated by whitespace. Both report every invalid
+// token with its byte offset.
+pub fn parse_hex(input: &str, mode: HexParseMode) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let mut errors = Vec::new();
+    match mode {
+        HexParseMode::Lenient => parse_hex_lenient(input, &mut bytes, &mut errors),
+        HexParseMode::Strict => parse_hex_strict(input, &mut bytes, &mut errors),
+    }
+    if errors.is_empty() {
+        return Ok(bytes);
+    }
+    let details: Vec<String> = errors
+        .iter()
+        .map(|error| format!("  offset {}: {} ({})", error.offset, error.token, error.reason))
+        .collect();
+    Err(anyhow::anyhow!("Invalid hex input:\n{}", details.join("\n")))
+}
+
+fn is_hex_separator(ch: char) -> bool {
+    ch.is_whitespace() || ",:;\"'{}[]()".contains(ch)
+}
+
+// The words around the language literals of bin2hex, matched with the separator after them
+const HEX_LITERAL_WRAPPERS: &[&str] = &["b\"", "b'", "byte{", "new Uint8Array(", "Uint8Array("];
+
+fn parse_hex_lenient(input: &str, bytes: &mut Vec<u8>, errors: &mut Vec<HexParseError>) {
+    for (offset, token) in split_with_offsets(input, is_hex_separator) {
+        let rest = &input[offset..];
+        if HEX_LITERAL_WRAPPERS.iter().any(|wrapper| rest.starts_with(wrapper)) {
+            continue;
+        }
+        // A backslash at the end of a line continues a Rust byte string
+        let after = &rest[token.len()..];
+        let token = match token.strip_suffix('\\') {
+            Some(escapes) if after.starts_with('\n') || after.starts_with("\r\n") => escapes,
+            _ => token,
+        };
+        if let Err(reason) = parse_hex_token(token, bytes) {
+            errors.push(HexParseError { offset, token: token.to_string(), reason });
+        }
+    }
+}
+
+// Non-empty pieces of `text` with their byte offsets
+fn split_with_offsets(text: &str, separator: impl Fn(char) -> bool) -> Vec<(usize, &str)> {
+    text.split(separator)
+        .filter(|piece| !piece.is_empty())
+        .map(|piece| (piece.as_ptr() as usize - text.as_ptr() as usize, piece))
+        .collect()
+}
+
+fn parse_hex_token(token: &str, bytes: &mut Vec<u8>) -> std::result::Result<(), &'static str> {
+    let mut parsed = Vec::new();
+    let mut rest = token;
+    while !rest.is_empty() {
+        let prefixes = ["0x", "0X", "\\x", "%"];
+        let prefixed = prefixes.iter().find_map(|prefix| rest.strip_prefix(prefix));
+        let after = prefixed.unwrap_or(rest);
+        let digits_len = after.find(|ch: char| !ch.is_ascii_hexdigit()).unwrap_or(after.len());
+        let digits = &after[..digits_len];
+        rest = &after[digits_len..];
+        if !rest.is_empty() && !prefixes.iter().any(|prefix| rest.starts_with(prefix)) {
+            return Err("not a hex digit");
+        }
+        match digits.len() {
+            0 => return Err("expected hex digits"),
+            // A lone digit is a byte only when written as 0x5 or \x5
+            1 if prefixed.is_some() => parsed.push(u8::from_str_radix(digits, 16).expect("hex digit")),
+            len if len % 2 == 1 => return Err("odd number of hex digits"),
+            _ => parsed.extend(digits.as_bytes().chunks(2).map(|pair| {
+                u8::from_str_radix(std::str::from_utf8(pair).expect("ASCII"), 16).expect("hex digits")
+            })),
+        }
+    }
+    bytes.extend(parsed);
+    Ok(())
+}
+
+// Tokens are split as in lenient mode, so each one is reported once with its own offset.
+// Separators other than whitespace are errors of their own.
+fn parse_hex_strict(input: &str, bytes: &mut Vec<u8>, errors: &mut Vec<HexParseError>) {
+    let separators = split_with_offsets(input, |ch| !is_hex_separator(ch) || ch.is_whitespace())
+        .into_iter()
+        .map(|(offset, token)| (offset, token, Err("separator not allowed in strict mode")));
+    let tokens = split_with_offsets(input, is_hex_separator)
+        .into_iter()
+        .map(|(offset, token)| (offset, token, parse_hex_strict_token(token)));
+    let mut pieces: Vec<_> = separators.chain(tokens).collect();
+    pieces.sort_by_key(|&(offset, _, _)| offset);
+    for (offset, token, result) in pieces {
+        match result {
+            Ok(parsed) => bytes.extend(parsed),
+            Err(reason) => errors.push(HexParseError { offset, token: token.to_string(), reason }),
+        }
+    }
+}
+
+// F09F8DA3, F0 or \xF0\x9F\x8D\xA3
+fn parse_hex_strict_token(token: &str) -> std::result::Result<Vec<u8>, &'static str> {
+    let pairs: Vec<&str> = match token.strip_prefix("\\x") {
+        Some(escapes) => escapes.split("\\x").collect(),
+        None if ["0x", "0X", "%"].iter().any(|prefix| token.starts_with(prefix)) => {
+            return Err("prefix not allowed in strict mode")
+        }
+        None if !token.bytes().all(|byte| byte.is_ascii_hexdigit()) => return Err("not a hex digit"),
+        None if token.len() % 2 == 1 => return Err("odd number of hex digits"),
+        None => (0..token.len()).step_by(2).map(|start| &token[start..start + 2]).collect(),
+    };
+    pairs
+        .iter()
+        .map(|pair| match pair.len() == 2 && pair.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            true => Ok(u8::from_str_radix(pair, 16).expect("hex digits")),
+            false => Err("expected two hex digits after \\x"),
+        })
+        .collect()
+}
+
+pub fn hex2bin(hex_input: &str) -> Result<String> {
+    let bytes = parse_hex(hex_input, HexParseMode::Lenient)?;
+
+    let result = String::from_utf8(bytes)
+        .map_err(|_| anyhow::anyhow!("Invalid UTF-8 sequence"))?;
+    
+    Ok(result)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DecodeMode {
+    Strict,
+    Raw,
+    Lossy,
+    Escaped,
+}
+
+pub fn hex2bin_with_mode(hex_input: &str, parse: HexParseMode, mode: DecodeMode) -> Result<Vec<u8>> {
+    decode_output(parse_hex(hex_input, parse)?, mode)
+}
+
+// Strict fails on invalid UTF-8 like hex2bin, raw returns the bytes as they are, lossy
+// replaces invalid sequences with U+FFFD like scrub, escaped writes them as \xHH
+pub fn decode_output(bytes: Vec<u8>, mode: DecodeMode) -> Result<Vec<u8>> {
+    match mode {
+        DecodeMode::Strict => {
+            let text = String::from_utf8(bytes).map_err(|_| anyhow::anyhow!("Invalid UTF-8 sequence"))?;
+            Ok(text.into_bytes())
+        }
+        DecodeMode::Raw => Ok(bytes),
+        DecodeMode::Lossy => Ok(String::from_utf8_lossy(&bytes).into_owned().into_bytes()),
+        DecodeMode::Escaped => Ok(escape_invalid_utf8(&bytes).into_bytes()),
+    }
+}
+
+pub fn escape_invalid_utf8(bytes: &[u8]) -> String {
+    utf8_segments(bytes)
+        .into_iter()
+        .map(|segment| match segment {
+            Utf8Segment::Valid { text, .. } => text.to_string(),
+            Utf8Segment::Invalid(sequence) => sequence
+                .bytes
+                .iter()
+                .map(|byte| format!("\\x{:02X}", byte))
+                .collect(),
+        })
+        .collect()
+}
+
+// Like hexdump -C, with the text column following UTF-8: each character is shown at its
+// first byte and "·" marks the rest of its bytes. Invalid bytes are shown as U+FFFD.
+// Every byte is one terminal column, so a wide character also takes the column of its
+// second byte, and one starting in the last column is moved to the start of the next row.
+pub fn hexdump(bytes: &[u8], lowercase: bool, color: bool) -> String {
+    let mut cells: Vec<(String, bool)> = Vec::with_capacity(bytes.len());
+    for segment in utf8_segments(bytes) {
+        match segment {
+            Utf8Segment::Valid { offset, text } => {
+                for (index, ch) in text.char_indices() {
+                    let (cell, width) = hexdump_cell(ch);
+                    let mut char_cells = vec!["\u{B7}".to_string(); ch.len_utf8()];
+                    // Wide characters are never shorter than three bytes
+                    let lead = if width == 2 && (offset + index) % 16 == 15 { 1 } else { 0 };
+                    char_cells[lead] = cell;
+                    if width == 2 {
+                        char_cells[lead + 1] = String::new();
+                    }
+                    cells.extend(char_cells.into_iter().map(|cell| (cell, false)));
+                }
+            }
+            Utf8Segment::Invalid(sequence) => {
+                cells.extend(sequence.bytes.iter().map(|_| ("\u{FFFD}".to_string(), true)));
+            }
         }
     }
-}
 
-pub fn unescape_unicode(input: &str) -> String {
-    let mut result = String::new();
-    let mut remaining = input;
-    
-    while !remaining.is_empty() {
-        if let Some(rest) = remaining.strip_prefix("\\u{") {
-            // Handle \u{...} format
-            if let Some(close_pos) = rest.find('}') {
-                let hex_part = &rest[..close_pos];
-                remaining = &rest[close_pos + 1..];
-                
-                if hex_part.is_empty() {
-                    result.push('\u{FFFD}');
-                } else if hex_part.chars().all(|c| c.is_ascii_hexdigit()) {
-                    match u32::from_str_radix(hex_part, 16) {
-                        Ok(code_point) => {
-                            if let Some(unicode_char) = char::from_u32(code_point) {
-                                result.push(unicode_char);
-                            } else {
-                                result.push('\u{FFFD}');
-                            }
-                        }
-                        Err(_) => {
-                            result.push('\u{FFFD}');
-                        }
-                    }
-                } else {
-                    result.push('\u{FFFD}');
-                }
-            } else {
-                // No closing brace found - consume all remaining characters
-                result.push('\u{FFFD}');
-                remaining = "";
-            }
-        } else if let Some(rest) = remaining.strip_prefix("\\u") {
-            // Handle \uXXXX format
-            if rest.len() >= 4 && rest.chars().take(4).all(|c| c.is_ascii_hexdigit()) {
-                let hex_part = &rest[..4];
-                remaining = &rest[4..];
-                
-                match u16::from_str_radix(hex_part, 16) {
-                    Ok(code_unit) => {
-                        // Handle surrogate pairs
-                        if (0xD800..=0xDBFF).contains(&code_unit) {
-                            // High surrogate - look for low surrogate
-                            if let Some(low_rest) = remaining.strip_prefix("\\u") {
-                                if low_rest.len() >= 4 && low_rest.chars().take(4).all(|c| c.is_ascii_hexdigit()) {
-                                    let low_hex = &low_rest[..4];
-                                    match u16::from_str_radix(low_hex, 16) {
-                                        Ok(low_surrogate) => {
-                                            if (0xDC00..=0xDFFF).contains(&low_surrogate) {
-                                                // Valid surrogate pair
-                                                let code_point = 0x10000 + ((code_unit as u32 - 0xD800) << 10) + (low_surrogate as u32 - 0xDC00);
-                                                if let Some(unicode_char) = char::from_u32(code_point) {
-                                                    result.push(unicode_char);
-                                                    remaining = &low_rest[4..];
-                                                } else {
-                                                    result.push('\u{FFFD}');
-                                                }
-                                            } else {
-                                                // Invalid low surrogate
-                                                result.push('\u{FFFD}');
-                                                result.push('\u{FFFD}');
-                                                remaining = &low_rest[4..];
-                                            }
-                                        }
-                                        Err(_) => {
-                                            // Invalid hex in low surrogate
-                                            result.push('\u{FFFD}');
-                                            result.push('\u{FFFD}');
-                                            remaining = &low_rest[4..];
-                                        }
-                                    }
-                                } else {
-                                    // Incomplete low surrogate
-                                    result.push('\u{FFFD}');
-                                }
-                            } else {
-                                // No low surrogate after high surrogate
-                                result.push('\u{FFFD}');
-                            }
-                        } else if (0xDC00..=0xDFFF).contains(&code_unit) {
-                            // Lone low surrogate
-                            result.push('\u{FFFD}');
-                        } else {
-                            // Regular BMP character
-                            if let Some(unicode_char) = char::from_u32(code_unit as u32) {
-                                result.push(unicode_char);
-                            } else {
-                                result.push('\u{FFFD}');
-                            }
-                        }
-                    }
-                    Err(_) => {
-                        // Invalid hex format
-                        result.push('\u{FFFD}');
-                    }
-                }
-            } else {
-                // Incomplete \uXXXX sequence
-                result.push('\u{FFFD}');
-                remaining = rest;
-            }
-        } else {
-            // Regular character or non-unicode escape
-            let ch = remaining.chars().next().unwrap();
-            result.push(ch);
-            remaining = &remaining[ch.len_utf8()..];
+    let highlight = |text: &str, invalid: bool| {
+        if color && invalid {
+            format!("\x1b[1;31m{}\x1b[0m", text)
+        } else {
+            text.to_string()
+        }
+    };
+    let hex = |value: usize, width: usize| {
+        if lowercase {
+            format!("{:0width$x}", value, width = width)
+        } else {
+            format!("{:0width$X}", value, width = width)
+        }
+    };
+
+    let mut output = String::new();
+    for (line, chunk) in bytes.chunks(16).enumerate() {
+        let offset = line * 16;
+        output.push_str(&hex(offset, 8));
+        output.push(' ');
+        for column in 0..16 {
+            if column % 8 == 0 {
+                output.push(' ');
+            }
+            match chunk.get(column) {
+                Some(&byte) => {
+                    output.push_str(&highlight(&hex(byte as usize, 2), cells[offset + column].1));
+                    output.push(' ');
+                }
+                None => output.push_str("   "),
+            }
+        }
+        output.push_str(" |");
+        for (text, invalid) in &cells[offset..offset + chunk.len()] {
+            output.push_str(&highlight(text, *invalid));
+        }
+        output.push_str("|\n");
+    }
+    if !bytes.is_empty() {
+        output.push_str(&format!("{}\n", hex(bytes.len(), 8)));
+    }
+    output
+}
+
+// Nonprintable characters are "." as in hexdump -C, marks sit on a dotted circle as in charts.
+// Returns the text and the number of terminal columns it takes.
+fn hexdump_cell(ch: char) -> (String, usize) {
+    if ch.is_ascii() {
+        return (if ch.is_ascii_graphic() || ch == ' ' { ch.to_string() } else { ".".to_string() }, 1);
+    }
+    match maps::general_category().get(ch) {
+        GeneralCategory::NonspacingMark | GeneralCategory::EnclosingMark => (format!("\u{25CC}{}", ch), 1),
+        _ if is_invisible(ch) => (".".to_string(), 1),
+        _ => (ch.to_string(), if is_wide(ch) { 2 } else { 1 }),
+    }
+}
+
+fn is_wide(ch: char) -> bool {
+    matches!(maps::east_asian_width().get(ch), EastAsianWidth::Wide | EastAsianWidth::Fullwidth)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BaseEncoding {
+    Base64,
+    Base64Url,
+    Base32,
+    Ascii85,
+}
+
+impl BaseEncoding {
+    fn name(&self) -> &'static str {
+        match self {
+            BaseEncoding::Base64 => "Base64",
+            BaseEncoding::Base64Url => "Base64url",
+            BaseEncoding::Base32 => "Base32",
+            BaseEncoding::Ascii85 => "Ascii85",
+        }
+    }
+
+    // Alphabet, bits per character and characters per padded group (RFC 4648)
+    fn rfc4648(&self) -> Option<(&'static [u8], u32, usize)> {
+        match self {
+            BaseEncoding::Base64 => Some((b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/", 6, 4)),
+            BaseEncoding::Base64Url => Some((b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_", 6, 4)),
+            BaseEncoding::Base32 => Some((b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567", 5, 8)),
+            BaseEncoding::Ascii85 => None,
+        }
+    }
+}
+
+// Padding only applies to the RFC 4648 encodings; Ascii85 is written without <~ ~> delimiters
+pub fn base_encode(bytes: &[u8], encoding: BaseEncoding, padding: bool) -> String {
+    let Some((alphabet, bits, group)) = encoding.rfc4648() else {
+        return ascii85_encode(bytes);
+    };
+    let mut output = String::new();
+    let mut buffer = 0u32;
+    let mut buffered = 0;
+    for &byte in bytes {
+        buffer = (buffer << 8) | byte as u32;
+        buffered += 8;
+        while buffered >= bits {
+            buffered -= bits;
+            output.push(alphabet[((buffer >> buffered) & ((1 << bits) - 1)) as usize] as char);
+        }
+    }
+    if buffered > 0 {
+        output.push(alphabet[((buffer << (bits - buffered)) & ((1 << bits) - 1)) as usize] as char);
+    }
+    if padding {
+        while !output.len().is_multiple_of(group) {
+            output.push('=');
+        }
+    }
+    output
+}
+
+// Whitespace is ignored so that MIME bodies with line breaks decode as they are, and
+// padding is optional (JWT segments have none)
+pub fn base_decode(input: &str, encoding: BaseEncoding) -> Result<Vec<u8>> {
+    let Some((alphabet, bits, _)) = encoding.rfc4648() else {
+        return ascii85_decode(input);
+    };
+    let chars: Vec<(usize, char)> = input
+        .chars()
+        .enumerate()
+        .filter(|(_, ch)| !ch.is_whitespace())
+        .collect();
+    let data_len = chars.iter().rposition(|&(_, ch)| ch != '=').map_or(0, |last| last + 1);
+
+    let mut bytes = Vec::new();
+    let mut buffer = 0u32;
+    let mut buffered = 0;
+    for &(position, ch) in &chars[..data_len] {
+        // Base32 is case-insensitive
+        let ch = if encoding == BaseEncoding::Base32 { ch.to_ascii_uppercase() } else { ch };
+        let value = alphabet
+            .iter()
+            .position(|&symbol| symbol as char == ch)
+            .ok_or_else(|| anyhow::anyhow!("Invalid {} character '{}' at position {}", encoding.name(), ch, position))?;
+        buffer = (buffer << bits) | value as u32;
+        buffered += bits;
+        if buffered >= 8 {
+            buffered -= 8;
+            bytes.push((buffer >> buffered) as u8);
+        }
+    }
+    if buffered >= bits {
+        return Err(anyhow::anyhow!("Invalid {} input: truncated after {} characters", encoding.name(), data_len));
+    }
+    Ok(bytes)
+}
+
+fn ascii85_encode(bytes: &[u8]) -> String {
+    let mut output = String::new();
+    for chunk in bytes.chunks(4) {
+        if chunk == [0, 0, 0, 0] {
+            output.push('z');
+            continue;
+        }
+        let mut word = [0u8; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        let mut value = u32::from_be_bytes(word);
+        let mut digits = [0u8; 5];
+        for digit in digits.iter_mut().rev() {
+            *digit = (value % 85) as u8 + b'!';
+            value /= 85;
+        }
+        // A partial group of n bytes is written as its first n + 1 digits
+        output.extend(digits[..chunk.len() + 1].iter().map(|&digit| digit as char));
+    }
+    output
+}
+
+fn ascii85_decode(input: &str) -> Result<Vec<u8>> {
+    let trimmed = input.trim();
+    let body = trimmed.strip_prefix("<~").unwrap_or(trimmed);
+    let body = body.strip_suffix("~>").unwrap_or(body);
+
+    let mut bytes = Vec::new();
+    let mut group = Vec::with_capacity(5);
+    for (position, ch) in body.chars().enumerate().filter(|(_, ch)| !ch.is_whitespace()) {
+        match ch {
+            'z' if group.is_empty() => bytes.extend_from_slice(&[0, 0, 0, 0]),
+            '!'..='u' => {
+                group.push(ch as u8 - b'!');
+                if group.len() == 5 {
+                    bytes.extend_from_slice(&ascii85_word(&group, position)?);
+                    group.clear();
+                }
+            }
+            _ => return Err(anyhow::anyhow!("Invalid Ascii85 character '{}' at position {}", ch, position)),
+        }
+    }
+    match group.len() {
+        0 => {}
+        1 => return Err(anyhow::anyhow!("Invalid Ascii85 input: truncated final group")),
+        len => {
+            let used = len - 1;
+            group.resize(5, b'u' - b'!');
+            bytes.extend_from_slice(&ascii85_word(&group, body.chars().count())?[..used]);
+        }
+    }
+    Ok(bytes)
+}
+
+fn ascii85_word(digits: &[u8], position: usize) -> Result<[u8; 4]> {
+    let value = digits.iter().fold(0u64, |value, &digit| value * 85 + digit as u64);
+    let value = u32::try_from(value)
+        .map_err(|_| anyhow::anyhow!("Invalid Ascii85 group ending at position {}: value too large", position))?;
+    Ok(value.to_be_bytes())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UrlComponent {
+    Path,
+    Query,
+    Form,
+    Userinfo,
+    Fragment,
+    All,
+}
+
+impl UrlComponent {
+    // The WHATWG URL percent-encode sets, plus "%" so that the output always decodes back
+    // to the input. Controls, space and non-ASCII bytes are encoded in every set.
+    fn encodes(&self, byte: u8) -> bool {
+        if !(0x21..0x7F).contains(&byte) || byte == b'%' {
+            return true;
+        }
+        let fragment = b"\"<>`".contains(&byte);
+        let query = b"\"#<>".contains(&byte);
+        let path = query || b"?`{}".contains(&byte);
+        let userinfo = path || b"/:;=@[\\]^|".contains(&byte);
+        let form = userinfo || b"$&+,!'()~".contains(&byte);
+        match self {
+            UrlComponent::Fragment => fragment,
+            UrlComponent::Query => query,
+            UrlComponent::Path => path,
+            UrlComponent::Userinfo => userinfo,
+            UrlComponent::Form => form,
+            // Everything but the RFC 3986 unreserved characters
+            UrlComponent::All => !(byte.is_ascii_alphanumeric() || b"-._~".contains(&byte)),
+        }
+    }
+}
+
+pub fn url_encode(bytes: &[u8], component: UrlComponent) -> String {
+    bytes
+        .iter()
+        .map(|&byte| match byte {
+            b' ' if component == UrlComponent::Form => "+".to_string(),
+            _ if component.encodes(byte) => format!("%{:02X}", byte),
+            _ => (byte as char).to_string(),
+        })
+        .collect()
+}
+
+// "%" not followed by two hex digits is kept as it is, like browsers do. With `form`,
+// "+" decodes to a space.
+pub fn url_decode(input: &str, form: bool) -> Vec<u8> {
+    let bytes = input.as_bytes();
+    let mut output = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = bytes
+            .get(index + 1..index + 3)
+            .filter(|hex| bytes[index] == b'%' && hex.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match (escaped, bytes[index]) {
+            (Some(byte), _) => {
+                output.push(byte);
+                index += 3;
+            }
+            (None, b'+') if form => {
+                output.push(b' ');
+                index += 1;
+            }
+            (None, byte) => {
+                output.push(byte);
+                index += 1;
+            }
+        }
+    }
+    output
+}
+
+#[derive(Debug, Clone)]
+pub enum InputFormat {
+    Binary,
+    Hex,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Utf8ErrorKind {
+    Truncated,
+    Overlong,
+    Surrogate,
+    OutOfRange,
+    UnexpectedContinuation,
+    // F8..FF, which no UTF-8 sequence starts with
+    InvalidByte,
+}
+
+impl Utf8ErrorKind {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Utf8ErrorKind::Truncated => "truncated sequence",
+            Utf8ErrorKind::Overlong => "overlong encoding",
+            Utf8ErrorKind::Surrogate => "encoded surrogate",
+            Utf8ErrorKind::OutOfRange => "out of range (above U+10FFFF)",
+            Utf8ErrorKind::UnexpectedContinuation => "unexpected continuation byte",
+            Utf8ErrorKind::InvalidByte => "invalid byte (never used in UTF-8)",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidSequence {
+    pub offset: usize,
+    pub bytes: Vec<u8>,
+    pub kind: Utf8ErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Utf8Segment<'a> {
+    Valid { offset: usize, text: &'a str },
+    Invalid(InvalidSequence),
+}
+
+pub fn utf8_segments(bytes: &[u8]) -> Vec<Utf8Segment<'_>> {
+    // Invalid sequences are maximal subparts, so each one matches a single
+    // U+FFFD from String::from_utf8_lossy
+    let mut segments = Vec::new();
+    let mut offset = 0;
+
+    while offset < bytes.len() {
+        let rest = &bytes[offset..];
+        let (valid_len, invalid_len) = match std::str::from_utf8(rest) {
+            Ok(_) => (rest.len(), 0),
+            Err(error) => (
+                error.valid_up_to(),
+                error.error_len().unwrap_or(rest.len() - error.valid_up_to()),
+            ),
+        };
+
+        if valid_len > 0 {
+            let text = std::str::from_utf8(&rest[..valid_len]).expect("validated above");
+            segments.push(Utf8Segment::Valid { offset, text });
+        }
+        if invalid_len > 0 {
+            let invalid = &rest[valid_len..valid_len + invalid_len];
+            let next = rest.get(valid_len + 1).copied();
+            segments.push(Utf8Segment::Invalid(InvalidSequence {
+                offset: offset + valid_len,
+                bytes: invalid.to_vec(),
+                kind: classify_utf8_error(invalid[0], next),
+            }));
+        }
+
+        offset += valid_len + invalid_len;
+    }
+
+    segments
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    pub line: usize,
+    pub column: usize,
+    pub sequence: InvalidSequence,
+}
+
+// Lines and columns start at 1. Columns count characters, with each earlier error counting
+// as one, as an editor shows it after lossy decoding. Unlike scrub, an overlong, surrogate
+// or out-of-range sequence is a single error together with the continuation bytes it
+// would have had, instead of one error per maximal subpart. F8..FF start no sequence, so
+// each of them is an error of its own.
+pub fn validate_utf8(bytes: &[u8]) -> Vec<ValidationError> {
+    let mut errors: Vec<ValidationError> = Vec::new();
+    let mut line = 1;
+    let mut column = 1;
+    // Continuation bytes still expected by the last error
+    let mut expected = 0;
+    for segment in utf8_segments(bytes) {
+        match segment {
+            Utf8Segment::Valid { text, .. } => {
+                expected = 0;
+                for ch in text.chars() {
+                    if ch == '\n' {
+                        line += 1;
+                        column = 1;
+                    } else {
+                        column += 1;
+                    }
+                }
+            }
+            Utf8Segment::Invalid(sequence) => {
+                if expected > 0 && sequence.kind == Utf8ErrorKind::UnexpectedContinuation {
+                    expected -= 1;
+                    errors.last_mut().expect("expected bytes follow an error").sequence.bytes.extend(sequence.bytes);
+                    continue;
+                }
+                expected = match sequence.kind {
+                    Utf8ErrorKind::Overlong | Utf8ErrorKind::Surrogate | Utf8ErrorKind::OutOfRange => {
+                        let length = match sequence.bytes[0] {
+                            0xC0..=0xDF => 2,
+                            0xE0..=0xEF => 3,
+                            // F5..F7 would start code points above U+10FFFF
+                            0xF0..=0xF7 => 4,
+                            _ => unreachable!("only lead bytes C0..F7 are overlong, surrogates or out of range"),
+                        };
+                        length - sequence.bytes.len().min(length)
+                    }
+                    _ => 0,
+                };
+                errors.push(ValidationError { line, column, sequence });
+                column += 1;
+            }
+        }
+    }
+    errors
+}
+
+// file:line:column: message, as compilers report it
+pub fn format_validation_error(source: &str, error: &ValidationError) -> String {
+    format!(
+        "{}:{}:{}: {}: {} (byte {})",
+        source,
+        error.line,
+        error.column,
+        error.sequence.kind.description(),
+        format_hex_bytes(&error.sequence.bytes),
+        error.sequence.offset
+    )
+}
+
+fn classify_utf8_error(lead: u8, next: Option<u8>) -> Utf8ErrorKind {
+    match (lead, next) {
+        (0x80..=0xBF, _) => Utf8ErrorKind::UnexpectedContinuation,
+        (0xC0 | 0xC1, _) => Utf8ErrorKind::Overlong,
+        (0xF5..=0xF7, _) => Utf8ErrorKind::OutOfRange,
+        (0xF8..=0xFF, _) => Utf8ErrorKind::InvalidByte,
+        (0xE0, Some(0x80..=0x9F)) | (0xF0, Some(0x80..=0x8F)) => Utf8ErrorKind::Overlong,
+        (0xED, Some(0xA0..=0xBF)) => Utf8ErrorKind::Surrogate,
+        (0xF4, Some(0x90..=0xBF)) => Utf8ErrorKind::OutOfRange,
+        _ => Utf8ErrorKind::Truncated,
+    }
+}
+
+pub fn input_to_bytes(input: &str, format: InputFormat) -> Result<Vec<u8>> {
+    let bytes = match format {
+        InputFormat::Binary => {
+            // Treat input as binary data (UTF-8 bytes)
+            input.as_bytes().to_vec()
+        }
+        InputFormat::Hex => parse_hex(input, HexParseMode::Lenient)?,
+    };
+
+    Ok(bytes)
+}
+
+pub fn scrub_invalid_utf8(input: &str, format: InputFormat) -> Result<String> {
+    let bytes = input_to_bytes(input, format)?;
+
+    // Use from_utf8_lossy to replace invalid UTF-8 sequences with U+FFFD
+    let result = String::from_utf8_lossy(&bytes).into_owned();
+    Ok(result)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScrubPolicy {
+    // One U+FFFD per maximal invalid subpart, like String::from_utf8_lossy
+    Lossy,
+    PerByte,
+    Replace(String),
+    Delete,
+    // Invalid bytes as \xNN and backslashes as \\, so the input can be recovered
+    Escape,
+    // U+DC80..U+DCFF as in Python's surrogateescape, written with surrogatepass as
+    // ED B2 80..ED B3 BF. The output is deliberately not valid UTF-8.
+    SurrogateEscape,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScrubOutput {
+    pub bytes: Vec<u8>,
+    // Invalid sequences for lossy, replace and delete, invalid bytes for the others
+    pub replacements: usize,
+}
+
+pub fn scrub_invalid_utf8_with_policy(input: &str, format: InputFormat, policy: &ScrubPolicy) -> Result<ScrubOutput> {
+    Ok(scrub_bytes(&input_to_bytes(input, format)?, policy))
+}
+
+pub fn scrub_bytes(bytes: &[u8], policy: &ScrubPolicy) -> ScrubOutput {
+    let mut output = Vec::with_capacity(bytes.len());
+    let mut replacements = 0;
+    for segment in utf8_segments(bytes) {
+        let sequence = match segment {
+            Utf8Segment::Valid { text, .. } if *policy == ScrubPolicy::Escape => {
+                output.extend_from_slice(text.replace('\\', "\\\\").as_bytes());
+                continue;
+            }
+            Utf8Segment::Valid { text, .. } => {
+                output.extend_from_slice(text.as_bytes());
+                continue;
+            }
+            Utf8Segment::Invalid(sequence) => sequence,
+        };
+        match policy {
+            ScrubPolicy::Lossy | ScrubPolicy::Replace(_) | ScrubPolicy::Delete => {
+                let replacement = match policy {
+                    ScrubPolicy::Replace(replacement) => replacement.as_str(),
+                    ScrubPolicy::Delete => "",
+                    _ => "\u{FFFD}",
+                };
+                output.extend_from_slice(replacement.as_bytes());
+                replacements += 1;
+            }
+            ScrubPolicy::PerByte | ScrubPolicy::Escape | ScrubPolicy::SurrogateEscape => {
+                for &byte in &sequence.bytes {
+                    match policy {
+                        ScrubPolicy::PerByte => output.extend_from_slice("\u{FFFD}".as_bytes()),
+                        ScrubPolicy::Escape => output.extend_from_slice(format!("\\x{:02X}", byte).as_bytes()),
+                        // Invalid bytes are never ASCII, so this is U+DC80..U+DCFF
+                        _ => {
+                            let surrogate = 0xDC00 | byte as u32;
+                            output.extend_from_slice(&[0xED, 0x80 | ((surrogate >> 6) & 0x3F) as u8, 0x80 | (surrogate & 0x3F) as u8]);
+                        }
+                    }
+                    replacements += 1;
+                }
+            }
+        }
+    }
+    ScrubOutput { bytes: output, replacements }
+}
+
+#[derive(Debug, Clone)]
+pub enum EscapeFormat {
+    Default,
+    Json,
+    Named,
+}
+
+pub fn escape_unicode(input: &str) -> String {
+    escape_unicode_with_format(input, EscapeFormat::Default)
+}
+
+pub fn escape_unicode_with_format(input: &str, format: EscapeFormat) -> String {
+    match format {
+        EscapeFormat::Default => {
+            input.chars()
+                .map(|ch| format!("\\u{{{:X}}}", ch as u32))
+                .collect::<Vec<_>>()
+                .join("")
+        }
+        EscapeFormat::Json => {
+            input.chars()
+                .flat_map(|ch| {
+                    let code_point = ch as u32;
+                    if code_point <= 0xFFFF {
+                        // BMP character - single \uXXXX
+                        vec![format!("\\u{:04X}", code_point)]
+                    } else {
+                        // Supplementary character - surrogate pair
+                        let adjusted = code_point - 0x10000;
+                        let high = 0xD800 + (adjusted >> 10);
+                        let low = 0xDC00 + (adjusted & 0x3FF);
+                        vec![format!("\\u{:04X}", high), format!("\\u{:04X}", low)]
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("")
+        }
+        EscapeFormat::Named => {
+            // Characters without a name (controls, private use, ...) fall back to \u{...}
+            input.chars()
+                .map(|ch| match character_name(ch) {
+                    Some(name) => format!("\\N{{{}}}", name),
+                    None => format!("\\u{{{:X}}}", ch as u32),
+                })
+                .collect::<Vec<_>>()
+                .join("")
+        }
+    }
+}
+
+pub fn unescape_unicode(input: &str) -> String {
+    let mut result = String::new();
+    let mut remaining = input;
+    
+    while !remaining.is_empty() {
+        if let Some(rest) = remaining.strip_prefix("\\N{") {
+            // Handle \N{NAME} format (names and aliases, case-insensitive)
+            if let Some(close_pos) = rest.find('}') {
+                let name = &rest[..close_pos];
+                remaining = &rest[close_pos + 1..];
+                result.push(lookup_char_by_name(name).unwrap_or('\u{FFFD}'));
+            } else {
+                // No closing brace found - consume all remaining characters
+                result.push('\u{FFFD}');
+                remaining = "";
+            }
+        } else if let Some(rest) = remaining.strip_prefix("\\u{") {
+            // Handle \u{...} format
+            if let Some(close_pos) = rest.find('}') {
+                let hex_part = &rest[..close_pos];
+                remaining = &rest[close_pos + 1..];
+                
+                if hex_part.is_empty() {
+                    result.push('\u{FFFD}');
+                } else if hex_part.chars().all(|c| c.is_ascii_hexdigit()) {
+                    match u32::from_str_radix(hex_part, 16) {
+                        Ok(code_point) => {
+                            if let Some(unicode_char) = char::from_u32(code_point) {
+                                result.push(unicode_char);
+                            } else {
+                                result.push('\u{FFFD}');
+                            }
+                        }
+                        Err(_) => {
+                            result.push('\u{FFFD}');
+                        }
+                    }
+                } else {
+                    result.push('\u{FFFD}');
+                }
+            } else {
+                // No closing brace found - consume all remaining characters
+                result.push('\u{FFFD}');
+                remaining = "";
+            }
+        } else if let Some(rest) = remaining.strip_prefix("\\u") {
+            // Handle \uXXXX format
+            if rest.len() >= 4 && rest.chars().take(4).all(|c| c.is_ascii_hexdigit()) {
+                let hex_part = &rest[..4];
+                remaining = &rest[4..];
+                
+                match u16::from_str_radix(hex_part, 16) {
+                    Ok(code_unit) => {
+                        // Handle surrogate pairs
+                        if (0xD800..=0xDBFF).contains(&code_unit) {
+                            // High surrogate - look for low surrogate
+                            if let Some(low_rest) = remaining.strip_prefix("\\u") {
+                                if low_rest.len() >= 4 && low_rest.chars().take(4).all(|c| c.is_ascii_hexdigit()) {
+                                    let low_hex = &low_rest[..4];
+                                    match u16::from_str_radix(low_hex, 16) {
+                                        Ok(low_surrogate) => {
+                                            if (0xDC00..=0xDFFF).contains(&low_surrogate) {
+                                                // Valid surrogate pair
+                                                let code_point = 0x10000 + ((code_unit as u32 - 0xD800) << 10) + (low_surrogate as u32 - 0xDC00);
+                                                if let Some(unicode_char) = char::from_u32(code_point) {
+                                                    result.push(unicode_char);
+                                                    remaining = &low_rest[4..];
+                                                } else {
+                                                    result.push('\u{FFFD}');
+                                                }
+                                            } else {
+                                                // Invalid low surrogate
+                                                result.push('\u{FFFD}');
+                                                result.push('\u{FFFD}');
+                                                remaining = &low_rest[4..];
+                                            }
+                                        }
+                                        Err(_) => {
+                                            // Invalid hex in low surrogate
+                                            result.push('\u{FFFD}');
+                                            result.push('\u{FFFD}');
+                                            remaining = &low_rest[4..];
+                                        }
+                                    }
+                                } else {
+                                    // Incomplete low surrogate
+                                    result.push('\u{FFFD}');
+                                }
+                            } else {
+                                // No low surrogate after high surrogate
+                                result.push('\u{FFFD}');
+                            }
+                        } else if (0xDC00..=0xDFFF).contains(&code_unit) {
+                            // Lone low surrogate
+                            result.push('\u{FFFD}');
+                        } else {
+                            // Regular BMP character
+                            if let Some(unicode_char) = char::from_u32(code_unit as u32) {
+                                result.push(unicode_char);
+                            } else {
+                                result.push('\u{FFFD}');
+                            }
+                        }
+                    }
+                    Err(_) => {
+                        // Invalid hex format
+                        result.push('\u{FFFD}');
+                    }
+                }
+            } else {
+                // Incomplete \uXXXX sequence
+                result.push('\u{FFFD}');
+                remaining = rest;
+            }
+        } else {
+            // Regular character or non-unicode escape
+            let ch = remaining.chars().next().unwrap();
+            result.push(ch);
+            remaining = &remaining[ch.len_utf8()..];
+        }
+    }
+    
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_iter_grapheme_basic() {
+        let result = iter_grapheme_icu4x("hello").unwrap();
+        assert_eq!(result, vec!["h", "e", "l", "l", "o"]);
+    }
+
+    #[test]
+    fn test_iter_grapheme_japanese() {
+        let result = iter_grapheme_icu4x("あいうえお").unwrap();
+        assert_eq!(result, vec!["あ", "い", "う", "え", "お"]);
+    }
+
+    #[test]
+    fn test_iter_grapheme_emoji() {
+        let result = iter_grapheme_icu4x("🍣🍺").unwrap();
+        assert_eq!(result, vec!["🍣", "🍺"]);
+    }
+
+    #[test]
+    fn test_iter_grapheme_complex_emoji() {
+        // 肌色の絵文字（結合文字）
+        let result = iter_grapheme_icu4x("👨‍💻👩‍🍳").unwrap();
+        assert_eq!(result, vec!["👨‍💻", "👩‍🍳"]);
+    }
+
+    #[test]
+    fn test_iter_grapheme_combining_characters() {
+        // 結合文字（濁点）
+        let result = iter_grapheme_icu4x("が").unwrap();
+        assert_eq!(result, vec!["が"]);
+    }
+
+    #[test]
+    fn test_iter_grapheme_mixed() {
+        let result = iter_grapheme_icu4x("あいうえお🍣🍺").unwrap();
+        assert_eq!(result, vec!["あ", "い", "う", "え", "お", "🍣", "🍺"]);
+    }
+
+    #[test]
+    fn test_iter_codepoint_basic() {
+        let result = iter_codepoint("hello");
+        assert_eq!(result, vec!["h", "e", "l", "l", "o"]);
+    }
+
+    #[test]
+    fn test_iter_codepoint_japanese() {
+        let result = iter_codepoint("あいう");
+        assert_eq!(result, vec!["あ", "い", "う"]);
+    }
+
+    #[test]
+    fn test_iter_codepoint_emoji() {
+        let result = iter_codepoint("🍣🍺");
+        assert_eq!(result, vec!["🍣", "🍺"]);
+    }
+
+    #[test]
+    fn test_iter_byte_basic() {
+        let result = iter_byte("hello");
+        assert_eq!(result, vec!["h", "e", "l", "l", "o"]);
+    }
+
+    #[test]
+    fn test_iter_byte_japanese() {
+        // 日本語文字は複数バイトになる
+        let result = iter_byte("あ");
+        assert_eq!(result.len(), 3); // UTF-8で「あ」は3バイト
+    }
+
+    #[test]
+    fn test_empty_string() {
+        let result = iter_grapheme_icu4x("").unwrap();
+        assert_eq!(result, Vec::<String>::new());
+
+        let result = iter_codepoint("");
+        assert_eq!(result, Vec::<String>::new());
+
+        let result = iter_byte("");
+        assert_eq!(result, Vec::<String>::new());
+    }
+
+    // Tests for count_units
+    #[test]
+    fn test_count_units_grapheme() {
+        let result = count_units("あいうえお🍣🍺", ProcessingMode::Grapheme).unwrap();
+        assert_eq!(result, 7);
+    }
+
+    #[test]
+    fn test_count_units_codepoint() {
+        let result = count_units("🍣🍺", ProcessingMode::Codepoint).unwrap();
+        assert_eq!(result, 2);
+    }
+
+    #[test]
+    fn test_count_units_byte() {
+        let result = count_units("hello", ProcessingMode::Byte).unwrap();
+        assert_eq!(result, 5);
+    }
+
+    #[test]
+    fn test_count_units_empty() {
+        let result = count_units("", ProcessingMode::Grapheme).unwrap();
+        assert_eq!(result, 0);
+    }
+
+    // Tests for take_units
+    #[test]
+    fn test_take_units_grapheme() {
+        let result = take_units("あいうえお🍣🍺", ProcessingMode::Grapheme, 3).unwrap();
+        assert_eq!(result, vec!["あ", "い", "う"]);
+    }
+
+    #[test]
+    fn test_take_units_codepoint() {
+        let result = take_units("hello", ProcessingMode::Codepoint, 2).unwrap();
+        assert_eq!(result, vec!["h", "e"]);
+    }
+
+    #[test]
+    fn test_take_units_byte() {
+        let result = take_units("abc", ProcessingMode::Byte, 2).unwrap();
+        assert_eq!(result, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_take_units_n_greater_than_length() {
+        let result = take_units("abc", ProcessingMode::Codepoint, 10).unwrap();
+        assert_eq!(result, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_take_units_zero() {
+        let result = take_units("abc", ProcessingMode::Codepoint, 0).unwrap();
+        assert_eq!(result, Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_take_units_empty_string() {
+        let result = take_units("", ProcessingMode::Grapheme, 5).unwrap();
+        assert_eq!(result, Vec::<String>::new());
+    }
+
+    // Tests for drop_units
+    #[test]
+    fn test_drop_units_grapheme() {
+        let result = drop_units("あいうえお🍣🍺", ProcessingMode::Grapheme, 2).unwrap();
+        assert_eq!(result, vec!["う", "え", "お", "🍣", "🍺"]);
+    }
+
+    #[test]
+    fn test_drop_units_codepoint() {
+        let result = drop_units("hello", ProcessingMode::Codepoint, 1).unwrap();
+        assert_eq!(result, vec!["e", "l", "l", "o"]);
+    }
+
+    #[test]
+    fn test_drop_units_byte() {
+        let result = drop_units("abc", ProcessingMode::Byte, 1).unwrap();
+        assert_eq!(result, vec!["b", "c"]);
+    }
+
+    #[test]
+    fn test_drop_units_n_greater_than_length() {
+        let result = drop_units("abc", ProcessingMode::Codepoint, 10).unwrap();
+        assert_eq!(result, Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_drop_units_zero() {
+        let result = drop_units("abc", ProcessingMode::Codepoint, 0).unwrap();
+        assert_eq!(result, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_drop_units_empty_string() {
+        let result = drop_units("", ProcessingMode::Grapheme, 5).unwrap();
+        assert_eq!(result, Vec::<String>::new());
+    }
+
+    // Tests for ord_characters function
+    #[test]
+    fn test_ord_default_format() {
+        let result = ord_characters("漢字🍺", false, false);
+        assert_eq!(result, vec!["0x6F22", "0x5B57", "0x1F37A"]);
+    }
+
+    #[test]
+    fn test_ord_lowercase() {
+        let result = ord_characters("漢字🍺", true, false);
+        assert_eq!(result, vec!["0x6f22", "0x5b57", "0x1f37a"]);
+    }
+
+    #[test]
+    fn test_ord_no_prefix() {
+        let result = ord_characters("漢字🍺", false, true);
+        assert_eq!(result, vec!["6F22", "5B57", "1F37A"]);
+    }
+
+    #[test]
+    fn test_ord_lowercase_no_prefix() {
+        let result = ord_characters("漢字🍺", true, true);
+        assert_eq!(result, vec!["6f22", "5b57", "1f37a"]);
+    }
+
+    #[test]
+    fn test_ord_ascii() {
+        let result = ord_characters("ABC", false, false);
+        assert_eq!(result, vec!["0x41", "0x42", "0x43"]);
+    }
+
+    #[test]
+    fn test_ord_empty_input() {
+        let result = ord_characters("", false, false);
+        assert_eq!(result, Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_ord_notations() {
+        let input = "漢🍺";
+        let cases = [
+            (OrdNotation::Unicode, vec!["U+6F22", "U+1F37A"]),
+            (OrdNotation::Decimal, vec!["28450", "127866"]),
+            (OrdNotation::Octal, vec!["0o67442", "0o371572"]),
+            (OrdNotation::HtmlDecimal, vec!["&#28450;", "&#127866;"]),
+            (OrdNotation::HtmlHex, vec!["&#x6F22;", "&#x1F37A;"]),
+            (OrdNotation::Css, vec!["\\6F22", "\\1F37A"]),
+            (OrdNotation::Escape, vec!["\\u{6F22}", "\\u{1F37A}"]),
+            (OrdNotation::Json, vec!["\\u6F22", "\\uD83C\\uDF7A"]),
+            (OrdNotation::Utf8, vec!["[E6 BC A2]", "[F0 9F 8D BA]"]),
+        ];
+        for (notation, expected) in cases {
+            assert_eq!(ord_characters_with_notation(input, notation, false, false), expected, "{:?}", notation);
+        }
+    }
+
+    #[test]
+    fn test_ord_notation_lowercase_and_padding() {
+        assert_eq!(ord_characters_with_notation("A\u{1F37A}", OrdNotation::Unicode, true, false), vec!["U+0041", "U+1f37a"]);
+        assert_eq!(ord_characters_with_notation("A", OrdNotation::Octal, false, true), vec!["101"]);
+        assert_eq!(ord_characters_with_notation("🍺", OrdNotation::Json, true, false), vec!["\\ud83c\\udf7a"]);
+    }
+
+    #[test]
+    fn test_ord_graphemes() {
+        let groups = ord_graphemes("が👍🏽", OrdNotation::Unicode, false, false).unwrap();
+        assert_eq!(groups, vec![
+            ("が".to_string(), vec!["U+304C".to_string()]),
+            ("👍🏽".to_string(), vec!["U+1F44D".to_string(), "U+1F3FD".to_string()]),
+        ]);
+    }
+
+    #[test]
+    fn test_explain_encoding_two_byte() {
+        let result = explain_encoding("é");
+        assert_eq!(result, "\
+U+00E9  é  LATIN SMALL LETTER E WITH ACUTE
+  code point  1110 1001 (8 bits)
+  UTF-8       110xxxxx 10xxxxxx
+                 00011   101001
+              11000011 10101001
+              C3       A9
+  UTF-16      xxxxxxxxxxxxxxxx
+              0000000011101001
+              0000000011101001
+              00E9
+");
+    }
+
+    #[test]
+    fn test_explain_encoding_surrogate_pair() {
+        let result = explain_encoding("🍣");
+        assert!(result.contains("  code point  1 1111 0011 0110 0011 (17 bits)\n"));
+        assert!(result.contains("  UTF-8       11110xxx 10xxxxxx 10xxxxxx 10xxxxxx\n"));
+        assert!(result.contains("              F0       9F       8D       A3\n"));
+        assert!(result.ends_with(concat!(
+            "  UTF-16      U+1F363 - U+10000 = 0F363 (20 bits)\n",
+            "              110110xxxxxxxxxx 110111xxxxxxxxxx\n",
+            "                    0000111100       1101100011\n",
+            "              1101100000111100 1101111101100011\n",
+            "              D83C             DF63\n",
+        )));
+    }
+
+    #[test]
+    fn test_explain_encoding_separates_characters() {
+        let result = explain_encoding("A\u{0}");
+        assert!(result.starts_with("U+0041  A  LATIN CAPITAL LETTER A\n  code point  100 0001 (7 bits)\n"));
+        assert!(result.contains("\n\nU+0000  \\u0000  <control-0000>\n  code point  0 (1 bit)\n"));
+    }
+
+    // Tests for chr_from_codepoints function
+    #[test]
+    fn test_chr_with_0x_prefix() {
+        let result = chr_from_codepoints(&["0x6f22".to_string(), "0x5b57".to_string(), "0x1f37a".to_string()]).unwrap();
+        assert_eq!(result, "漢字🍺");
+    }
+
+    #[test]
+    fn test_chr_without_prefix() {
+        let result = chr_from_codepoints(&["6F22".to_string(), "5B57".to_string(), "1F37A".to_string()]).unwrap();
+        assert_eq!(result, "漢字🍺");
+    }
+
+    #[test]
+    fn test_chr_mixed_case() {
+        let result = chr_from_codepoints(&["0x6F22".to_string(), "5b57".to_string(), "1f37a".to_string()]).unwrap();
+        assert_eq!(result, "漢字🍺");
+    }
+
+    #[test]
+    fn test_chr_ascii() {
+        let result = chr_from_codepoints(&["0x41".to_string(), "0x42".to_string(), "0x43".to_string()]).unwrap();
+        assert_eq!(result, "ABC");
+    }
+
+    #[test]
+    fn test_chr_invalid_hex() {
+        let result = chr_from_codepoints(&["0xGGGG".to_string()]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_chr_invalid_codepoint() {
+        let result = chr_from_codepoints(&["0x110000".to_string()]); // > U+10FFFF
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_chr_empty_input() {
+        let result = chr_from_codepoints(&[]).unwrap();
+        assert_eq!(result, "");
+    }
+
+    #[test]
+    fn test_chr_uppercase_prefix() {
+        let result = chr_from_codepoints(&["0X41".to_string(), "0X42".to_string()]).unwrap();
+        assert_eq!(result, "AB");
+    }
+
+    #[test]
+    fn test_chr_notations() {
+        let tokens: Vec<String> = ["U+3042", "u+3042", "#12354", "&#12354;", "&#x3042;", "\\u{3042}", "\\u3042", "3042"]
+            .iter()
+            .map(|t| t.to_string())
+            .collect();
+        assert_eq!(chr_from_codepoints(&tokens).unwrap(), "あ".repeat(8));
+    }
+
+    #[test]
+    fn test_chr_bare_lowercase_hex() {
+        let tokens: Vec<String> = ["d7", "5b57", "1f37a", "dad"].iter().map(|t| t.to_string()).collect();
+        assert_eq!(chr_from_codepoints(&tokens).unwrap(), "\u{D7}字🍺\u{DAD}");
+        assert!(chr_from_codepoints(&["d800".to_string()]).unwrap_err().to_string().contains("Surrogate code point U+D800"));
+        let codepoints = ord_characters_with_notation("×🍺", OrdNotation::Hex, true, true);
+        assert_eq!(chr_from_codepoints(&codepoints).unwrap(), "×🍺");
+    }
+
+    #[test]
+    fn test_chr_separators_in_argument() {
+        let result = chr_from_codepoints(&["U+41, U+42 0x43".to_string()]).unwrap();
+        assert_eq!(result, "ABC");
+    }
+
+    #[test]
+    fn test_chr_range() {
+        let result = chr_from_codepoints(&["U+3041..U+3096".to_string()]).unwrap();
+        assert_eq!(result.chars().count(), 0x3096 - 0x3041 + 1);
+        assert!(result.starts_with("ぁあぃ") && result.ends_with("ゖ"));
+        assert_eq!(chr_from_codepoints(&["41-43".to_string()]).unwrap(), "ABC");
+    }
+
+    #[test]
+    fn test_chr_range_skip_unassigned() {
+        let range = ["U+3094..U+309A".to_string()];
+        assert_eq!(chr_from_codepoints(&range).unwrap().chars().count(), 7);
+        assert_eq!(chr_from_codepoints_with_options(&range, true).unwrap(), "ゔゕゖ\u{3099}\u{309A}");
+    }
+
+    #[test]
+    fn test_chr_range_skips_surrogates() {
+        let result = chr_from_codepoints(&["U+D7FF..U+E000".to_string()]).unwrap();
+        assert_eq!(result, "\u{D7FF}\u{E000}");
+    }
+
+    #[test]
+    fn test_chr_error_positions() {
+        let err = chr_from_codepoints(&["U+41".to_string(), "U+42 zz".to_string()]).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid code point at token 3: zz");
+        let err = chr_from_codepoints(&["U+D800".to_string()]).unwrap_err();
+        assert_eq!(err.to_string(), "Surrogate code point U+D800 at token 1: U+D800");
+        let err = chr_from_codepoints(&["U+3096..U+3041".to_string()]).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid range at token 1: U+3096..U+3041 (start is after end)");
+        assert!(chr_from_codepoints(&["+41".to_string()]).is_err());
+    }
+
+    // Complex Unicode tests
+    #[test]
+    fn test_complex_emoji_count() {
+        let result = count_units("👨‍💻👩‍🍳", ProcessingMode::Grapheme).unwrap();
+        assert_eq!(result, 2);
+    }
+
+    #[test]
+    fn test_complex_emoji_take() {
+        let result = take_units("👨‍💻👩‍🍳", ProcessingMode::Grapheme, 1).unwrap();
+        assert_eq!(result, vec!["👨‍💻"]);
+    }
+
+    #[test]
+    fn test_complex_emoji_drop() {
+        let result = drop_units("👨‍💻👩‍🍳", ProcessingMode::Grapheme, 1).unwrap();
+        assert_eq!(result, vec!["👩‍🍳"]);
+    }
+
+    // Tests for dump functionality
+    #[test]
+    fn test_dump_graphemes_text_simple() {
+        let result = dump_graphemes("🍣", DumpFormat::Text).unwrap();
+        assert!(result.contains("Cluster 0: 🍣 (1 codepoint)"));
+        assert!(result.contains("U+1F363"));
+        assert!(result.contains("SUSHI"));
+    }
+
+    #[test]
+    fn test_dump_graphemes_json() {
+        let result = dump_graphemes("🍣", DumpFormat::Json).unwrap();
+        assert!(result.contains("cluster_index"));
+        assert!(result.contains("codepoints"));
+        assert!(result.contains("U+1F363"));
+        assert!(result.contains("SUSHI"));
+    }
+
+    #[test]
+    fn test_dump_graphemes_jsonl() {
+        let result = dump_graphemes("🍣🍺", DumpFormat::Jsonl).unwrap();
+        let lines: Vec<&str> = result.trim().split('\n').collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("🍣"));
+        assert!(lines[1].contains("🍺"));
+    }
+
+    #[test]
+    fn test_dump_complex_emoji() {
+        let result = dump_graphemes("👩‍👩‍👧‍👦", DumpFormat::Text).unwrap();
+        assert!(result.contains("Cluster 0: 👩‍👩‍👧‍👦 (7 codepoints)"));
+        assert!(result.contains("WOMAN"));
+        assert!(result.contains("ZERO WIDTH JOINER"));
+        assert!(result.contains("GIRL"));
+        assert!(result.contains("BOY"));
+    }
+
+    #[test]
+    fn test_get_unicode_name() {
+        assert_eq!(get_unicode_name('A'), "LATIN CAPITAL LETTER A");
+        assert_eq!(get_unicode_name('🍣'), "SUSHI");
+        assert_eq!(get_unicode_name('\u{200D}'), "ZERO WIDTH JOINER");
+        assert_eq!(get_unicode_name('\u{7}'), "<control-0007>");
+        assert_eq!(get_unicode_name('\u{E000}'), "<private-use-E000>");
+        assert_eq!(get_unicode_name('\u{FFFF}'), "<noncharacter-FFFF>");
+        assert_eq!(get_unicode_name('\u{FDD0}'), "<noncharacter-FDD0>");
+        assert_eq!(get_unicode_name('\u{378}'), "<reserved-0378>");
+        assert_eq!(get_unicode_name('\u{10FFFD}'), "<private-use-10FFFD>");
+        // Added in Unicode 15.1, after the unicode_names2 data
+        assert_eq!(get_unicode_name('\u{31EF}'), "IDEOGRAPHIC DESCRIPTION CHARACTER SUBTRACTION");
+        assert_eq!(get_unicode_name('\u{2FFF}'), "IDEOGRAPHIC DESCRIPTION CHARACTER ROTATION");
+        assert_eq!(get_unicode_name('\u{2EBF0}'), "CJK UNIFIED IDEOGRAPH-2EBF0");
+        assert_eq!(get_unicode_name('\u{17000}'), "TANGUT IDEOGRAPH-17000");
+        assert_eq!(get_unicode_name('\u{18D08}'), "TANGUT IDEOGRAPH-18D08");
+        assert_eq!(get_unicode_name('\u{18800}'), "TANGUT COMPONENT-001");
+    }
+
+    #[test]
+    fn test_every_assigned_code_point_has_a_name_or_label() {
+        for ch in ('\0'..=char::MAX).filter(|&ch| maps::general_category().get(ch) != GeneralCategory::Unassigned) {
+            assert_ne!(get_unicode_name(ch), "UNKNOWN CHARACTER", "U+{:04X}", ch as u32);
         }
     }
-    
-    result
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_get_char_properties_hiragana() {
+        let properties = get_char_properties('あ');
+        assert_eq!(properties.general_category, "Other_Letter");
+        assert_eq!(properties.script, "Hiragana");
+        assert_eq!(properties.block, "Hiragana");
+        assert_eq!(properties.bidi_class, "Left_To_Right");
+        assert_eq!(properties.east_asian_width, "Wide");
+        assert_eq!(properties.age.as_deref(), Some("1.1"));
+        assert_eq!(properties.numeric_value, None);
+    }
 
     #[test]
-    fn test_iter_grapheme_basic() {
-        let result = iter_grapheme_icu4x("hello").unwrap();
-        assert_eq!(result, vec!["h", "e", "l", "l", "o"]);
+    fn test_get_char_properties_script_extensions() {
+        // KATAKANA-HIRAGANA PROLONGED SOUND MARK is Common but used by both kana scripts
+        let properties = get_char_properties('ー');
+        assert_eq!(properties.script, "Common");
+        assert_eq!(properties.script_extensions, vec!["Hiragana", "Katakana"]);
     }
 
     #[test]
-    fn test_iter_grapheme_japanese() {
-        let result = iter_grapheme_icu4x("あいうえお").unwrap();
-        assert_eq!(result, vec!["あ", "い", "う", "え", "お"]);
+    fn test_get_char_properties_combining_mark() {
+        // COMBINING KATAKANA-HIRAGANA VOICED SOUND MARK
+        let properties = get_char_properties('\u{3099}');
+        assert_eq!(properties.general_category, "Nonspacing_Mark");
+        assert_eq!(properties.canonical_combining_class, 8);
+        assert_eq!(properties.grapheme_cluster_break, "Extend");
     }
 
     #[test]
-    fn test_iter_grapheme_emoji() {
-        let result = iter_grapheme_icu4x("🍣🍺").unwrap();
-        assert_eq!(result, vec!["🍣", "🍺"]);
+    fn test_get_char_properties_numeric_value() {
+        assert_eq!(get_char_properties('½').numeric_value.as_deref(), Some("1/2"));
+        assert_eq!(get_char_properties('万').numeric_value.as_deref(), Some("10000"));
+        assert_eq!(get_char_properties('7').numeric_value.as_deref(), Some("7"));
     }
 
     #[test]
-    fn test_iter_grapheme_complex_emoji() {
-        // 肌色の絵文字（結合文字）
-        let result = iter_grapheme_icu4x("👨‍💻👩‍🍳").unwrap();
-        assert_eq!(result, vec!["👨‍💻", "👩‍🍳"]);
+    fn test_get_char_properties_unassigned() {
+        let properties = get_char_properties('\u{0378}');
+        assert_eq!(properties.general_category, "Unassigned");
+        assert_eq!(properties.age, None);
     }
 
     #[test]
-    fn test_iter_grapheme_combining_characters() {
-        // 結合文字（濁点）
-        let result = iter_grapheme_icu4x("が").unwrap();
-        assert_eq!(result, vec!["が"]);
+    fn test_dump_graphemes_properties() {
+        let result = dump_graphemes("🍣", DumpFormat::Text).unwrap();
+        assert!(result.contains("category: Other_Symbol"));
+        assert!(result.contains("block: Miscellaneous Symbols and Pictographs"));
+        assert!(result.contains("age: 6.0"));
+
+        let result = dump_graphemes("🍣", DumpFormat::Jsonl).unwrap();
+        assert!(result.contains("\"general_category\":\"Other_Symbol\""));
+        assert!(result.contains("\"east_asian_width\":\"Wide\""));
     }
 
     #[test]
-    fn test_iter_grapheme_mixed() {
-        let result = iter_grapheme_icu4x("あいうえお🍣🍺").unwrap();
-        assert_eq!(result, vec!["あ", "い", "う", "え", "お", "🍣", "🍺"]);
+    fn test_dump_graphemes_encodings() {
+        let result = dump_graphemes("🍣", DumpFormat::Text).unwrap();
+        assert!(result.contains("utf-8: F0 9F 8D A3  utf-16: D83C DF63"));
+
+        let result = dump_graphemes("が", DumpFormat::Jsonl).unwrap();
+        assert!(result.contains("\"utf8\":\"E3 81 8C\""));
+        assert!(result.contains("\"utf16\":\"304C\""));
     }
 
     #[test]
-    fn test_iter_codepoint_basic() {
-        let result = iter_codepoint("hello");
-        assert_eq!(result, vec!["h", "e", "l", "l", "o"]);
+    fn test_dump_graphemes_offsets() {
+        let result = dump_graphemes("a🍣👨‍💻x", DumpFormat::Text).unwrap();
+        assert!(result.contains("Cluster 0: a (1 codepoint)  offset: byte 0, codepoint 0, utf-16 0"));
+        assert!(result.contains("Cluster 1: 🍣 (1 codepoint)  offset: byte 1, codepoint 1, utf-16 1"));
+        assert!(result.contains("Cluster 2: 👨‍💻 (3 codepoints)  offset: byte 5, codepoint 2, utf-16 3"));
+        assert!(result.contains("Cluster 3: x (1 codepoint)  offset: byte 16, codepoint 5, utf-16 8"));
     }
 
     #[test]
-    fn test_iter_codepoint_japanese() {
-        let result = iter_codepoint("あいう");
-        assert_eq!(result, vec!["あ", "い", "う"]);
+    fn test_dump_graphemes_csv() {
+        let result = dump_graphemes("a👨‍💻", DumpFormat::Csv).unwrap();
+        let lines: Vec<&str> = result.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert!(lines[0].starts_with("cluster_index,codepoint_index,cluster,char,codepoint,name,"));
+        assert!(lines[1].starts_with("0,0,a,a,U+0061,LATIN SMALL LETTER A,61,0061,"));
+        assert!(lines[3].starts_with("1,1,👨‍💻,\\u200d,U+200D,ZERO WIDTH JOINER,"));
     }
 
     #[test]
-    fn test_iter_codepoint_emoji() {
-        let result = iter_codepoint("🍣🍺");
-        assert_eq!(result, vec!["🍣", "🍺"]);
+    fn test_dump_graphemes_csv_quoting() {
+        let result = dump_graphemes("\",", DumpFormat::Csv).unwrap();
+        let lines: Vec<&str> = result.lines().collect();
+        assert!(lines[1].starts_with("0,0,\"\"\"\",\"\"\"\",U+0022,QUOTATION MARK,"));
+        assert!(lines[2].starts_with("1,0,\",\",\",\",U+002C,COMMA,"));
     }
 
     #[test]
-    fn test_iter_byte_basic() {
-        let result = iter_byte("hello");
-        assert_eq!(result, vec!["h", "e", "l", "l", "o"]);
+    fn test_dump_graphemes_tsv_without_header() {
+        let options = DumpOptions { header: false, ..Default::default() };
+        let result = dump_graphemes_with_options("\t🍣", DumpFormat::Tsv, &options).unwrap();
+        let lines: Vec<&str> = result.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("0\t0\t\\u0009\t\\u0009\tU+0009\t"));
+        assert!(lines[1].starts_with("1\t0\t🍣\t🍣\tU+1F363\tSUSHI\tF0 9F 8D A3\tD83C DF63\tOther_Symbol\t"));
     }
 
     #[test]
-    fn test_iter_byte_japanese() {
-        // 日本語文字は複数バイトになる
-        let result = iter_byte("あ");
-        assert_eq!(result.len(), 3); // UTF-8で「あ」は3バイト
+    fn test_is_invisible() {
+        assert!(is_invisible('\u{200D}'));
+        assert!(is_invisible('\u{FE0F}'));
+        assert!(is_invisible('\u{202E}'));
+        assert!(is_invisible('\u{3000}'));
+        assert!(is_invisible('\n'));
+        assert!(!is_invisible(' '));
+        assert!(!is_invisible('あ'));
+        assert!(!is_invisible('\u{0301}'));
     }
 
     #[test]
-    fn test_empty_string() {
-        let result = iter_grapheme_icu4x("").unwrap();
-        assert_eq!(result, Vec::<String>::new());
+    fn test_dump_graphemes_markdown() {
+        let result = dump_graphemes("a|👨‍💻", DumpFormat::Markdown).unwrap();
+        let lines: Vec<&str> = result.lines().collect();
+        assert!(lines[0].starts_with("| Cluster | Grapheme | Index | Char | Code point |"));
+        assert_eq!(lines[3], "| 1 | `\\|` | 0 | `\\|` | U+007C | 7C | VERTICAL LINE | Math_Symbol | Common |");
+        assert_eq!(lines[4], "| 2 | `👨\\u200d💻` | 0 | `👨` | U+1F468 | F0 9F 91 A8 | MAN | Other_Symbol | Common |");
+        assert_eq!(lines[5], "|  |  | 1 | `\\u200d` | U+200D | E2 80 8D | ZERO WIDTH JOINER | Format | Inherited |");
+    }
 
-        let result = iter_codepoint("");
-        assert_eq!(result, Vec::<String>::new());
+    #[test]
+    fn test_dump_graphemes_markdown_backtick() {
+        let result = dump_graphemes("`", DumpFormat::Markdown).unwrap();
+        assert!(result.contains("| 0 | `` ` `` | 0 | `` ` `` | U+0060 |"));
+    }
 
-        let result = iter_byte("");
-        assert_eq!(result, Vec::<String>::new());
+    #[test]
+    fn test_dump_graphemes_html() {
+        let options = DumpOptions { lang: Some("ja".to_string()), ..Default::default() };
+        let result = dump_graphemes_with_options("<が\u{FE0F}", DumpFormat::Html, &options).unwrap();
+        assert!(result.starts_with("<table class=\"mojibox-dump\" lang=\"ja\" dir=\"ltr\">"));
+        assert!(result.contains("<td rowspan=\"1\">0</td><td rowspan=\"1\" dir=\"auto\"><code>&lt;</code></td>"));
+        assert!(result.contains("<td rowspan=\"2\">1</td><td rowspan=\"2\" dir=\"auto\"><code>が\\ufe0f</code></td>"));
+        assert!(result.contains("<td>1</td><td dir=\"auto\"><code>\\ufe0f</code></td><td>U+FE0F</td>"));
+        assert!(result.ends_with("</table>\n"));
     }
 
-    // Tests for count_units
     #[test]
-    fn test_count_units_grapheme() {
-        let result = count_units("あいうえお🍣🍺", ProcessingMode::Grapheme).unwrap();
-        assert_eq!(result, 7);
+    fn test_dump_graphemes_html_direction() {
+        let result = dump_graphemes("שלום", DumpFormat::Html).unwrap();
+        assert!(result.starts_with("<table class=\"mojibox-dump\" lang=\"und\" dir=\"rtl\">"));
+
+        let result = dump_graphemes("123", DumpFormat::Html).unwrap();
+        assert!(result.starts_with("<table class=\"mojibox-dump\" lang=\"und\" dir=\"auto\">"));
     }
 
     #[test]
-    fn test_count_units_codepoint() {
-        let result = count_units("🍣🍺", ProcessingMode::Codepoint).unwrap();
-        assert_eq!(result, 2);
+    fn test_classify_special_char() {
+        assert_eq!(classify_special_char('\u{0007}'), Some(SpecialCharKind::Control));
+        assert_eq!(classify_special_char('\u{200C}'), Some(SpecialCharKind::Joiner));
+        assert_eq!(classify_special_char('\u{200D}'), Some(SpecialCharKind::Joiner));
+        assert_eq!(classify_special_char('\u{FE0F}'), Some(SpecialCharKind::VariationSelector));
+        assert_eq!(classify_special_char('\u{E0100}'), Some(SpecialCharKind::VariationSelector));
+        assert_eq!(classify_special_char('\u{202E}'), Some(SpecialCharKind::BidiControl));
+        assert_eq!(classify_special_char('\u{3099}'), Some(SpecialCharKind::CombiningMark));
+        assert_eq!(classify_special_char('\u{3000}'), Some(SpecialCharKind::Whitespace));
+        assert_eq!(classify_special_char('\u{E000}'), Some(SpecialCharKind::PrivateUse));
+        assert_eq!(classify_special_char(' '), None);
+        assert_eq!(classify_special_char('あ'), None);
     }
 
     #[test]
-    fn test_count_units_byte() {
-        let result = count_units("hello", ProcessingMode::Byte).unwrap();
-        assert_eq!(result, 5);
+    fn test_dump_graphemes_escapes_invisible_chars() {
+        let result = dump_graphemes("❤\u{FE0F}\u{202E}", DumpFormat::Text).unwrap();
+        assert!(result.contains("[1] \\ufe0f    U+FE0F  VARIATION SELECTOR-16"));
+        assert!(result.contains("[0] \\u202e    U+202E  RIGHT-TO-LEFT OVERRIDE"));
+        assert!(!result.contains("\x1b["));
     }
 
     #[test]
-    fn test_count_units_empty() {
-        let result = count_units("", ProcessingMode::Grapheme).unwrap();
-        assert_eq!(result, 0);
+    fn test_dump_graphemes_color() {
+        let options = DumpOptions { color: true, ..Default::default() };
+        let result = dump_graphemes_with_options("a\u{FE0F}\u{202E}", DumpFormat::Text, &options).unwrap();
+        assert!(result.contains("  [0] a    U+0061  LATIN SMALL LETTER A\n"));
+        assert!(result.contains("  \x1b[36m[1] \\ufe0f    U+FE0F  VARIATION SELECTOR-16  (alias: VS16)\x1b[0m\n"));
+        assert!(result.contains("  \x1b[97;41m[0] \\u202e    U+202E  RIGHT-TO-LEFT OVERRIDE  (alias: RLO)\x1b[0m\n"));
+
+        let result = dump_graphemes("\u{FE0F}", DumpFormat::Jsonl).unwrap();
+        assert!(result.contains("\"special\":\"variation_selector\""));
     }
 
-    // Tests for take_units
     #[test]
-    fn test_take_units_grapheme() {
-        let result = take_units("あいうえお🍣🍺", ProcessingMode::Grapheme, 3).unwrap();
-        assert_eq!(result, vec!["あ", "い", "う"]);
+    fn test_get_codepoint_label() {
+        assert_eq!(get_codepoint_label(0x7), Some(CodepointLabel::Control));
+        assert_eq!(get_codepoint_label(0x9F), Some(CodepointLabel::Control));
+        assert_eq!(get_codepoint_label(0xD800), Some(CodepointLabel::Surrogate));
+        assert_eq!(get_codepoint_label(0xF0000), Some(CodepointLabel::PrivateUse));
+        assert_eq!(get_codepoint_label(0x1FFFE), Some(CodepointLabel::Noncharacter));
+        assert_eq!(get_codepoint_label(0xE0080), Some(CodepointLabel::Reserved));
+        assert_eq!(get_codepoint_label(0x41), None);
+        assert_eq!(get_codepoint_label(0x110000), None);
+        assert_eq!(format_codepoint_label(CodepointLabel::Surrogate, 0xDC00), "<surrogate-DC00>");
     }
 
     #[test]
-    fn test_take_units_codepoint() {
-        let result = take_units("hello", ProcessingMode::Codepoint, 2).unwrap();
-        assert_eq!(result, vec!["h", "e"]);
+    fn test_get_name_aliases() {
+        assert_eq!(get_name_aliases('\u{7}'), vec!["ALERT", "BEL", "BELL"]);
+        assert_eq!(get_name_aliases('\u{FEFF}'), vec!["BYTE ORDER MARK", "BOM", "ZWNBSP"]);
+        assert_eq!(get_name_aliases('\u{1D0C5}'), vec!["BYZANTINE MUSICAL SYMBOL FTHORA SKLIRON CHROMA VASIS"]);
+        assert!(get_name_aliases('A').is_empty());
     }
 
     #[test]
-    fn test_take_units_byte() {
-        let result = take_units("abc", ProcessingMode::Byte, 2).unwrap();
-        assert_eq!(result, vec!["a", "b"]);
+    fn test_dump_codepoint_labels() {
+        let result = dump_graphemes("\u{7}\u{FEFF}", DumpFormat::Text).unwrap();
+        assert!(result.contains("  [0] \\u0007    U+0007  <control-0007>  (alias: ALERT, BEL, BELL)"));
+        assert!(result.contains("U+FEFF  ZERO WIDTH NO-BREAK SPACE  (alias: BYTE ORDER MARK, BOM, ZWNBSP)"));
+
+        let result = dump_graphemes("\u{E000}", DumpFormat::Json).unwrap();
+        assert!(result.contains("\"name\": \"<private-use-E000>\""));
+        assert!(result.contains("\"label\": \"private_use\""));
+
+        let result = dump_graphemes("\u{7}", DumpFormat::Csv).unwrap();
+        assert!(result.lines().next().unwrap().ends_with(",numeric_value,label,aliases"));
+        assert!(result.contains(",<control-0007>,"));
+        assert!(result.trim_end().ends_with(",control,ALERT; BEL; BELL"));
     }
 
     #[test]
-    fn test_take_units_n_greater_than_length() {
-        let result = take_units("abc", ProcessingMode::Codepoint, 10).unwrap();
-        assert_eq!(result, vec!["a", "b", "c"]);
+    fn test_get_unihan_info() {
+        let unihan = get_unihan_info('漢').unwrap();
+        assert_eq!(unihan.mandarin.as_deref(), Some("hàn"));
+        assert_eq!(unihan.jis_x0208.as_deref(), Some("20-33"));
+        assert_eq!(unihan.shift_jis.as_deref(), Some("8A BF"));
+
+        // IBM extension: FB FC in Windows-31J, but in neither JIS X 0208 nor Shift_JIS
+        let unihan = get_unihan_info('髙').unwrap();
+        assert_eq!(unihan.jis_x0208, None);
+        assert_eq!(unihan.shift_jis, None);
+
+        // First and last kanji of level 1 and level 2, on both sides of the lead byte gap
+        for (ch, jis, sjis) in [('亜', "16-01", "88 9F"), ('腕', "47-51", "98 72"), ('弌', "48-01", "98 9F"), ('熙', "84-06", "EA A4")] {
+            let unihan = get_unihan_info(ch).unwrap();
+            assert_eq!(unihan.jis_x0208.as_deref(), Some(jis), "{}", ch);
+            assert_eq!(unihan.shift_jis.as_deref(), Some(sjis), "{}", ch);
+        }
+        // Within JIS X 0208, Shift_JIS and Windows-31J agree
+        for ch in ('\u{4E00}'..='\u{9FFF}').filter(|&ch| get_unihan_info(ch).is_some_and(|unihan| unihan.shift_jis.is_some())) {
+            let text = ch.to_string();
+            let (sjis, _, _) = encoding_rs::SHIFT_JIS.encode(&text);
+            assert_eq!(get_unihan_info(ch).unwrap().shift_jis, Some(format_hex_bytes(&sjis)), "{}", ch);
+        }
+
+        let unihan = get_unihan_info('𠮷').unwrap();
+        assert_eq!(unihan.jis_x0208, None);
+        assert_eq!(unihan.shift_jis, None);
+
+        assert_eq!(get_unihan_info('あ'), None);
+        assert_eq!(get_unihan_info('A'), None);
     }
 
     #[test]
-    fn test_take_units_zero() {
-        let result = take_units("abc", ProcessingMode::Codepoint, 0).unwrap();
-        assert_eq!(result, Vec::<String>::new());
+    fn test_dump_unihan() {
+        let result = dump_graphemes("漢", DumpFormat::Text).unwrap();
+        assert!(!result.contains("unihan:"));
+
+        let options = DumpOptions { unihan: true, ..Default::default() };
+        let result = dump_graphemes_with_options("漢a", DumpFormat::Text, &options).unwrap();
+        assert!(result.contains("      unihan: mandarin: hàn  jis: 20-33  shift_jis: 8A BF\n"));
+        assert_eq!(result.matches("unihan:").count(), 1);
+
+        let result = dump_graphemes_with_options("字", DumpFormat::Jsonl, &options).unwrap();
+        assert!(result.contains("\"unihan\":{\"mandarin\":\"zì\",\"jis_x0208\":\"27-90\",\"shift_jis\":\"8E 9A\"}"));
     }
 
     #[test]
-    fn test_take_units_empty_string() {
-        let result = take_units("", ProcessingMode::Grapheme, 5).unwrap();
-        assert_eq!(result, Vec::<String>::new());
+    fn test_classify_emoji() {
+        let test_cases = vec![
+            ("🍣", EmojiSequenceType::Basic, "sushi", true, true),
+            ("©️", EmojiSequenceType::Basic, "copyright", true, true),
+            ("©", EmojiSequenceType::Basic, "copyright", false, false),
+            ("1️⃣", EmojiSequenceType::Keycap, "keycap: 1", true, true),
+            ("🇯🇵", EmojiSequenceType::Flag, "flag: Japan", true, true),
+            ("🏴\u{E0067}\u{E0062}\u{E0065}\u{E006E}\u{E0067}\u{E007F}", EmojiSequenceType::Tag, "flag: England", true, true),
+            ("👍🏽", EmojiSequenceType::Modifier, "thumbs up: medium skin tone", true, true),
+            ("👨‍💻", EmojiSequenceType::Zwj, "man technologist", true, true),
+        ];
+
+        for (input, sequence_type, name, rgi, emoji_presentation) in test_cases {
+            let emoji = classify_emoji(input).unwrap();
+            assert_eq!(emoji.sequence_type, sequence_type, "Failed for input: {}", input);
+            assert_eq!(emoji.name.as_deref(), Some(name), "Failed for input: {}", input);
+            assert_eq!(emoji.rgi, rgi, "Failed for input: {}", input);
+            assert_eq!(emoji.emoji_presentation, emoji_presentation, "Failed for input: {}", input);
+        }
     }
 
-    // Tests for drop_units
     #[test]
-    fn test_drop_units_grapheme() {
-        let result = drop_units("あいうえお🍣🍺", ProcessingMode::Grapheme, 2).unwrap();
-        assert_eq!(result, vec!["う", "え", "お", "🍣", "🍺"]);
+    fn test_classify_emoji_non_emoji() {
+        assert_eq!(classify_emoji("a"), None);
+        assert_eq!(classify_emoji("1"), None);
+        assert_eq!(classify_emoji("あ"), None);
+        assert_eq!(classify_emoji(""), None);
     }
 
     #[test]
-    fn test_drop_units_codepoint() {
-        let result = drop_units("hello", ProcessingMode::Codepoint, 1).unwrap();
-        assert_eq!(result, vec!["e", "l", "l", "o"]);
+    fn test_dump_emoji_info() {
+        let result = dump_graphemes("あ👨‍💻", DumpFormat::Text).unwrap();
+        assert!(result.contains("  emoji: ZWJ sequence  name: man technologist  rgi: yes  presentation: emoji"));
+        assert_eq!(result.matches("emoji:").count(), 1);
+
+        let result = dump_graphemes("a🍣", DumpFormat::Jsonl).unwrap();
+        let lines: Vec<&str> = result.lines().collect();
+        assert!(!lines[0].contains("\"emoji\""));
+        assert!(lines[1].contains("\"emoji\":{\"sequence_type\":\"basic\",\"name\":\"sushi\",\"rgi\":true,\"emoji_presentation\":true}"));
+    }
+
+    #[test]
+    fn test_dump_filter_non_ascii() {
+        let options = DumpOptions {
+            filter: DumpFilter { non_ascii: true, ..Default::default() },
+            ..Default::default()
+        };
+        let result = dump_graphemes_with_options("ab🍣c", DumpFormat::Text, &options).unwrap();
+        assert!(result.contains("Cluster 2: 🍣 (1 codepoint)  offset: byte 2, codepoint 2, utf-16 2"));
+        assert_eq!(result.matches("Cluster ").count(), 1);
+
+        let result = dump_bytes_with_options(b"a\xFFb", DumpFormat::Jsonl, &options).unwrap();
+        assert_eq!(result.lines().count(), 1);
+        assert!(result.contains("\"cluster_index\":1"));
+        assert!(result.contains("\"kind\":\"invalid_byte\""));
+    }
+
+    #[test]
+    fn test_dump_filter_multi_codepoint_and_invisible() {
+        let input = "ae\u{301}\u{200B}👨‍💻";
+        let options = DumpOptions {
+            filter: DumpFilter { multi_codepoint: true, ..Default::default() },
+            ..Default::default()
+        };
+        let result = dump_graphemes_with_options(input, DumpFormat::Jsonl, &options).unwrap();
+        let indices: Vec<&str> = result.lines().map(|line| &line[..18]).collect();
+        assert_eq!(indices, vec!["{\"cluster_index\":1", "{\"cluster_index\":3"]);
+
+        let options = DumpOptions {
+            filter: DumpFilter { invisible: true, ..Default::default() },
+            ..Default::default()
+        };
+        let result = dump_graphemes_with_options(input, DumpFormat::Jsonl, &options).unwrap();
+        let indices: Vec<&str> = result.lines().map(|line| &line[..18]).collect();
+        assert_eq!(indices, vec!["{\"cluster_index\":2", "{\"cluster_index\":3"]);
+
+        // Filters combine: only clusters matching all of them are shown
+        let options = DumpOptions {
+            filter: DumpFilter { multi_codepoint: true, invisible: true, ..Default::default() },
+            ..Default::default()
+        };
+        let result = dump_bytes_with_options(format!("{}\u{FF}", input).as_bytes(), DumpFormat::Jsonl, &options).unwrap();
+        assert_eq!(result.lines().count(), 1);
+        assert!(result.starts_with("{\"cluster_index\":3"));
     }
 
     #[test]
-    fn test_drop_units_byte() {
-        let result = drop_units("abc", ProcessingMode::Byte, 1).unwrap();
-        assert_eq!(result, vec!["b", "c"]);
+    fn test_dump_filter_category_and_script() {
+        let options = DumpOptions {
+            filter: DumpFilter { category: Some("Lu".to_string()), ..Default::default() },
+            ..Default::default()
+        };
+        let result = dump_graphemes_with_options("aBc", DumpFormat::Csv, &options).unwrap();
+        assert_eq!(result.lines().count(), 2);
+        assert!(result.contains("\n1,0,B,B,U+0042,"));
+
+        for category in ["Format", "Cf", "C"] {
+            let options = DumpOptions {
+                filter: DumpFilter { category: Some(category.to_string()), ..Default::default() },
+                ..Default::default()
+            };
+            let result = dump_graphemes_with_options("a\u{200B}b", DumpFormat::Jsonl, &options).unwrap();
+            assert_eq!(result.lines().count(), 1, "Failed for category: {}", category);
+        }
+
+        // Cyrillic а (U+0430) hidden in a Latin word
+        for script in ["Cyrl", "Cyrillic"] {
+            let options = DumpOptions {
+                filter: DumpFilter { script: Some(script.to_string()), ..Default::default() },
+                ..Default::default()
+            };
+            let result = dump_graphemes_with_options("pаypal", DumpFormat::Jsonl, &options).unwrap();
+            assert_eq!(result.lines().count(), 1, "Failed for script: {}", script);
+            assert!(result.contains("\"codepoint\":\"U+0430\""));
+        }
     }
 
     #[test]
-    fn test_drop_units_n_greater_than_length() {
-        let result = drop_units("abc", ProcessingMode::Codepoint, 10).unwrap();
-        assert_eq!(result, Vec::<String>::new());
+    fn test_dump_filter_unknown_names() {
+        let options = DumpOptions {
+            filter: DumpFilter { category: Some("Foo".to_string()), ..Default::default() },
+            ..Default::default()
+        };
+        let error = dump_graphemes_with_options("a", DumpFormat::Text, &options).unwrap_err();
+        assert_eq!(error.to_string(), "Unknown general category: Foo");
+
+        let options = DumpOptions {
+            filter: DumpFilter { script: Some("Klingon".to_string()), ..Default::default() },
+            ..Default::default()
+        };
+        let error = dump_graphemes_with_options("a", DumpFormat::Text, &options).unwrap_err();
+        assert_eq!(error.to_string(), "Unknown script: Klingon");
     }
 
     #[test]
-    fn test_drop_units_zero() {
-        let result = drop_units("abc", ProcessingMode::Codepoint, 0).unwrap();
-        assert_eq!(result, vec!["a", "b", "c"]);
+    fn test_lookup_char_by_name() {
+        assert_eq!(lookup_char_by_name("SUSHI"), Some('🍣'));
+        assert_eq!(lookup_char_by_name("ideographic space"), Some('\u{3000}'));
+        assert_eq!(lookup_char_by_name("BYTE ORDER MARK"), Some('\u{FEFF}'));
+        assert_eq!(lookup_char_by_name("alert"), Some('\u{7}'));
+        assert_eq!(lookup_char_by_name("NOT A CHARACTER NAME"), None);
+        assert_eq!(lookup_char_by_name("ideographic description character subtraction"), Some('\u{31EF}'));
+        assert_eq!(lookup_char_by_name("CJK UNIFIED IDEOGRAPH-2EBF0"), Some('\u{2EBF0}'));
+        assert_eq!(lookup_char_by_name("TANGUT IDEOGRAPH-17000"), Some('\u{17000}'));
+        assert_eq!(lookup_char_by_name("CJK UNIFIED IDEOGRAPH-2EE5E"), None);
     }
 
     #[test]
-    fn test_drop_units_empty_string() {
-        let result = drop_units("", ProcessingMode::Grapheme, 5).unwrap();
-        assert_eq!(result, Vec::<String>::new());
+    fn test_find_chars_by_name() {
+        assert_eq!(find_chars_by_name("sushi"), vec!['🍣']);
+
+        let result = find_chars_by_name("HIRAGANA LETTER SMALL *");
+        assert_eq!(&result[..3], &['ぁ', 'ぃ', 'ぅ']);
+        assert!(result.iter().all(|&ch| get_unicode_name(ch).starts_with("HIRAGANA LETTER SMALL ")));
+
+        assert_eq!(find_chars_by_name("HIRAGANA LETTER ?A"), vec!['か', 'が', 'さ', 'ざ', 'た', 'だ', 'な', 'は', 'ば', 'ぱ', 'ま', 'や', 'ら', 'わ']);
+        assert_eq!(find_chars_by_name("with snow"), vec!['🌨']);
+        assert!(find_chars_by_name("XYZZYQ").is_empty());
     }
 
-    // Tests for ord_characters function
     #[test]
-    fn test_ord_default_format() {
-        let result = ord_characters("漢字🍺", false, false);
-        assert_eq!(result, vec!["0x6F22", "0x5B57", "0x1F37A"]);
+    fn test_wildcard_match() {
+        assert!(wildcard_match(b"A*C", b"ABBC"));
+        assert!(wildcard_match(b"*", b""));
+        assert!(wildcard_match(b"A?C", b"ABC"));
+        assert!(wildcard_match(b"*B*B", b"ABCAB"));
+        assert!(!wildcard_match(b"A?C", b"AC"));
+        assert!(!wildcard_match(b"A*D", b"ABC"));
     }
 
     #[test]
-    fn test_ord_lowercase() {
-        let result = ord_characters("漢字🍺", true, false);
-        assert_eq!(result, vec!["0x6f22", "0x5b57", "0x1f37a"]);
+    fn test_fuzzy_find_chars_by_name() {
+        let result = fuzzy_find_chars_by_name("IDEOGRAFIC SPACE", 3);
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[0], '\u{3000}');
+        assert_eq!(fuzzy_find_chars_by_name("SUSHY", 1), vec!['🍣']);
     }
 
     #[test]
-    fn test_ord_no_prefix() {
-        let result = ord_characters("漢字🍺", false, true);
-        assert_eq!(result, vec!["6F22", "5B57", "1F37A"]);
+    fn test_format_codepoint_lines() {
+        assert_eq!(
+            format_codepoint_lines(&['🍣', '\u{FEFF}']),
+            "[0] 🍣    U+1F363  SUSHI\n[1] \\ufeff    U+FEFF  ZERO WIDTH NO-BREAK SPACE  (alias: BYTE ORDER MARK, BOM, ZWNBSP)\n"
+        );
     }
 
     #[test]
-    fn test_ord_lowercase_no_prefix() {
-        let result = ord_characters("漢字🍺", true, true);
-        assert_eq!(result, vec!["6f22", "5b57", "1f37a"]);
+    fn test_resolve_chart_range() {
+        assert_eq!(resolve_chart_range("Katakana").unwrap(), (0x30A0, 0x30FF));
+        assert_eq!(resolve_chart_range("halfwidth_and_fullwidth_forms").unwrap(), (0xFF00, 0xFFEF));
+        assert_eq!(resolve_chart_range("Latin-1 Supplement").unwrap(), (0x80, 0xFF));
+        assert_eq!(resolve_chart_range("U+30A0..U+30FF").unwrap(), (0x30A0, 0x30FF));
+        assert_eq!(resolve_chart_range("30a0-30ff").unwrap(), (0x30A0, 0x30FF));
+        assert_eq!(resolve_chart_range("0x1F600..0x1F64F").unwrap(), (0x1F600, 0x1F64F));
+        assert!(resolve_chart_range("30FF..30A0").is_err());
+        assert!(resolve_chart_range("0..110000").is_err());
+        assert_eq!(
+            resolve_chart_range("Klingon").unwrap_err().to_string(),
+            "Unknown block or code point range: Klingon"
+        );
     }
 
     #[test]
-    fn test_ord_ascii() {
-        let result = ord_characters("ABC", false, false);
-        assert_eq!(result, vec!["0x41", "0x42", "0x43"]);
+    fn test_chart_chars() {
+        let result = chart_chars("Katakana", None).unwrap();
+        assert_eq!(result.len(), 96);
+        assert_eq!(result[0], '゠');
+
+        // Unassigned code points and surrogates are skipped
+        assert_eq!(chart_chars("0376..037B", None).unwrap(), vec!['Ͷ', 'ͷ', 'ͺ', 'ͻ']);
+        assert_eq!(chart_chars("D7FB..E000", None).unwrap(), vec!['\u{D7FB}', '\u{E000}']);
+
+        let result = chart_chars("Basic Latin", Some("Lu")).unwrap();
+        assert_eq!(result, ('A'..='Z').collect::<Vec<_>>());
+        assert!(chart_chars("Basic Latin", Some("Foo")).is_err());
     }
 
     #[test]
-    fn test_ord_empty_input() {
-        let result = ord_characters("", false, false);
-        assert_eq!(result, Vec::<String>::new());
+    fn test_format_chart_grid() {
+        let chars = chart_chars("U+3040..U+3052", Some("Lo")).unwrap();
+        assert_eq!(
+            format_chart(&chars, ChartFormat::Grid).unwrap(),
+            "         0  1  2  3  4  5  6  7  8  9  A  B  C  D  E  F\n\
+             U+3040      ぁ あ ぃ い ぅ う ぇ え ぉ お か が き ぎ く\n\
+             U+3050   ぐ け げ\n"
+        );
+
+        let result = format_chart(&['\u{301}', '\u{7}'], ChartFormat::Grid).unwrap();
+        assert!(result.contains("U+0000                        \u{25CC}\n"));
+        assert!(result.contains("U+0300      \u{25CC}\u{301}\n"));
     }
 
-    // Tests for chr_from_codepoints function
     #[test]
-    fn test_chr_with_0x_prefix() {
-        let result = chr_from_codepoints(&["0x6f22".to_string(), "0x5b57".to_string(), "0x1f37a".to_string()]).unwrap();
-        assert_eq!(result, "漢字🍺");
+    fn test_format_chart_lines_and_json() {
+        let chars = chart_chars("U+41..U+42", None).unwrap();
+        assert_eq!(
+            format_chart(&chars, ChartFormat::Lines).unwrap(),
+            "[0] A    U+0041  LATIN CAPITAL LETTER A\n[1] B    U+0042  LATIN CAPITAL LETTER B\n"
+        );
+        let json: serde_json::Value = serde_json::from_str(&format_chart(&chars, ChartFormat::Json).unwrap()).unwrap();
+        assert_eq!(json[1]["codepoint"], "U+0042");
+        assert_eq!(json[1]["general_category"], "Uppercase_Letter");
     }
 
     #[test]
-    fn test_chr_without_prefix() {
-        let result = chr_from_codepoints(&["6F22".to_string(), "5B57".to_string(), "1F37A".to_string()]).unwrap();
-        assert_eq!(result, "漢字🍺");
+    fn test_undump_roundtrip() {
+        let input = "あ🍣👨‍💻\u{7}\u{FEFF}a\tb";
+        for format in [DumpFormat::Json, DumpFormat::Jsonl] {
+            let dump = dump_graphemes(input, format).unwrap();
+            assert_eq!(undump(&dump).unwrap(), input);
+        }
+        assert_eq!(undump("[]").unwrap(), "");
+        assert_eq!(undump("").unwrap(), "");
     }
 
     #[test]
-    fn test_chr_mixed_case() {
-        let result = chr_from_codepoints(&["0x6F22".to_string(), "5b57".to_string(), "1f37a".to_string()]).unwrap();
-        assert_eq!(result, "漢字🍺");
+    fn test_undump_invalid_sequences() {
+        let bytes = b"A\xF0\x9F\x8DB\xFF";
+        let dump = dump_bytes(bytes, DumpFormat::Jsonl).unwrap();
+        assert_eq!(undump_bytes(&dump).unwrap(), bytes);
+        assert!(undump(&dump).is_err());
     }
 
     #[test]
-    fn test_chr_ascii() {
-        let result = chr_from_codepoints(&["0x41".to_string(), "0x42".to_string(), "0x43".to_string()]).unwrap();
-        assert_eq!(result, "ABC");
+    fn test_undump_reports_mismatches() {
+        let dump = dump_graphemes("ab", DumpFormat::Json)
+            .unwrap()
+            .replace("\"display\": \"a\"", "\"display\": \"x\"")
+            .replace("\"char\": \"b\"", "\"char\": \"y\"");
+        let error = undump(&dump).unwrap_err().to_string();
+        assert!(error.contains("cluster 0: display \"x\" does not match code points U+0061 (\"a\")"));
+        assert!(error.contains("cluster 1: char \"y\" does not match U+0062"));
     }
 
     #[test]
-    fn test_chr_invalid_hex() {
-        let result = chr_from_codepoints(&["0xGGGG".to_string()]);
-        assert!(result.is_err());
+    fn test_undump_uses_edited_codepoints() {
+        let dump = dump_graphemes("a", DumpFormat::Jsonl)
+            .unwrap()
+            .replace("\"display\":\"a\"", "\"display\":\"b\"")
+            .replace("\"char\":\"a\"", "\"char\":\"b\"")
+            .replace("U+0061", "U+0062");
+        assert_eq!(undump(&dump).unwrap(), "b");
     }
 
     #[test]
-    fn test_chr_invalid_codepoint() {
-        let result = chr_from_codepoints(&["0x110000".to_string()]); // > U+10FFFF
-        assert!(result.is_err());
+    fn test_undump_trimmed_dump() {
+        let dump = concat!(
+            "{\"display\":\"か\u{3099}\",\"codepoints\":[{\"codepoint\":\"U+304B\"},{\"codepoint\":\"U+3099\"}]}\n",
+            "{\"display\":\"🍣\",\"codepoints\":[{\"char\":\"🍣\",\"codepoint\":\"U+1F363\"}]}\n",
+            "{\"display\":\"\\\\xFF\",\"invalid\":{\"bytes\":\"FF\"}}\n",
+        );
+        assert_eq!(undump_bytes(dump).unwrap(), b"\xE3\x81\x8B\xE3\x82\x99\xF0\x9F\x8D\xA3\xFF");
+        let error = undump("[{\"display\":\"a\",\"codepoints\":[]}, {\"display\":\"b\",\"codepoints\":[{\"codepoint\":\"U+0063\"}]}]").unwrap_err();
+        assert!(error.to_string().contains("cluster 0: display \"a\" has no code points"));
+        assert!(error.to_string().contains("cluster 1: display \"b\" does not match code points U+0063 (\"c\")"));
     }
 
     #[test]
-    fn test_chr_empty_input() {
-        let result = chr_from_codepoints(&[]).unwrap();
-        assert_eq!(result, "");
+    fn test_undump_rejects_gaps_in_cluster_indices() {
+        let options = DumpOptions {
+            filter: DumpFilter { multi_codepoint: true, ..Default::default() },
+            ..Default::default()
+        };
+        let dump = dump_graphemes_with_options("ae\u{301}", DumpFormat::Jsonl, &options).unwrap();
+        let error = undump(&dump).unwrap_err().to_string();
+        assert!(error.contains("Cluster 1 is at position 0"), "{}", error);
+        assert!(error.contains("filtered dump"));
+        let dump = "[{\"cluster_index\":0,\"display\":\"a\",\"codepoints\":[{\"codepoint\":\"U+0061\"}]},\
+            {\"cluster_index\":2,\"display\":\"b\",\"codepoints\":[{\"codepoint\":\"U+0062\"}]}]";
+        assert!(undump(dump).unwrap_err().to_string().contains("Cluster 2 is at position 1"));
     }
 
     #[test]
-    fn test_chr_uppercase_prefix() {
-        let result = chr_from_codepoints(&["0X41".to_string(), "0X42".to_string()]).unwrap();
-        assert_eq!(result, "AB");
+    fn test_undump_checks_schema_version() {
+        let dump = dump_graphemes("a", DumpFormat::Jsonl).unwrap();
+        assert!(dump.contains(&format!("\"schema_version\":{}", DUMP_SCHEMA_VERSION)));
+        let dump = dump.replace(&format!("\"schema_version\":{}", DUMP_SCHEMA_VERSION), "\"schema_version\":2");
+        let error = undump(&dump).unwrap_err().to_string();
+        assert_eq!(error, format!("Cluster 0: dump schema version 2 is not supported (expected {})", DUMP_SCHEMA_VERSION));
     }
 
-    // Complex Unicode tests
     #[test]
-    fn test_complex_emoji_count() {
-        let result = count_units("👨‍💻👩‍🍳", ProcessingMode::Grapheme).unwrap();
-        assert_eq!(result, 2);
+    fn test_undump_invalid_json() {
+        assert!(undump("not json").unwrap_err().to_string().contains("line 1"));
+        assert!(undump("[{\"cluster_index\": 0}]").is_err());
+        let dump = dump_graphemes("a", DumpFormat::Jsonl).unwrap().replace("U+0061", "U+D800");
+        assert!(undump(&dump).unwrap_err().to_string().contains("invalid code point: U+D800"));
     }
 
     #[test]
-    fn test_complex_emoji_take() {
-        let result = take_units("👨‍💻👩‍🍳", ProcessingMode::Grapheme, 1).unwrap();
-        assert_eq!(result, vec!["👨‍💻"]);
+    fn test_dump_schema() {
+        let schema: serde_json::Value = serde_json::from_str(DUMP_SCHEMA).unwrap();
+        assert_eq!(schema["title"], format!("mojibox dump, schema version {}", DUMP_SCHEMA_VERSION));
+        assert_eq!(schema["$defs"]["cluster"]["properties"]["schema_version"]["const"], DUMP_SCHEMA_VERSION);
+        let dump = dump_graphemes("a", DumpFormat::Json).unwrap();
+        let clusters: serde_json::Value = serde_json::from_str(&dump).unwrap();
+        // Dump writes every field the schema knows, apart from the ones that depend on the input
+        for (definition, value, optional) in [
+            ("cluster", &clusters[0], &["emoji", "invalid"][..]),
+            ("codepoint", &clusters[0]["codepoints"][0], &["unihan"][..]),
+        ] {
+            for field in schema["$defs"][definition]["properties"].as_object().unwrap().keys() {
+                assert!(value.get(field).is_some() || optional.contains(&field.as_str()), "Missing field: {}", field);
+            }
+        }
+        assert_eq!(schema["$defs"]["cluster"]["required"], serde_json::json!(["display"]));
+        assert_eq!(schema["$defs"]["codepoint"]["required"], serde_json::json!(["codepoint"]));
     }
 
     #[test]
-    fn test_complex_emoji_drop() {
-        let result = drop_units("👨‍💻👩‍🍳", ProcessingMode::Grapheme, 1).unwrap();
-        assert_eq!(result, vec!["👩‍🍳"]);
+    fn test_utf8_segments_valid() {
+        let segments = utf8_segments("あ🍣".as_bytes());
+        assert_eq!(segments, vec![Utf8Segment::Valid { offset: 0, text: "あ🍣" }]);
+        assert!(utf8_segments(b"").is_empty());
     }
 
-    // Tests for dump functionality
     #[test]
-    fn test_dump_graphemes_text_simple() {
-        let result = dump_graphemes("🍣", DumpFormat::Text).unwrap();
-        assert!(result.contains("Cluster 0: 🍣 (1 codepoint)"));
-        assert!(result.contains("U+1F363"));
-        assert!(result.contains("SUSHI"));
+    fn test_utf8_segments_error_kinds() {
+        let test_cases = vec![
+            (&b"\xF0\x9F\x8D"[..], Utf8ErrorKind::Truncated, 3),
+            (&b"\xE3\x81A"[..], Utf8ErrorKind::Truncated, 2),
+            (&b"\xC0\x80"[..], Utf8ErrorKind::Overlong, 1),
+            (&b"\xE0\x80\x80"[..], Utf8ErrorKind::Overlong, 1),
+            (&b"\xF0\x80\x80\x80"[..], Utf8ErrorKind::Overlong, 1),
+            (&b"\xED\xA0\x80"[..], Utf8ErrorKind::Surrogate, 1),
+            (&b"\xF4\x90\x80\x80"[..], Utf8ErrorKind::OutOfRange, 1),
+            (&b"\xF5"[..], Utf8ErrorKind::OutOfRange, 1),
+            (&b"\xF7\xBF"[..], Utf8ErrorKind::OutOfRange, 1),
+            (&b"\xF8"[..], Utf8ErrorKind::InvalidByte, 1),
+            (&b"\xFE"[..], Utf8ErrorKind::InvalidByte, 1),
+            (&b"\xFF"[..], Utf8ErrorKind::InvalidByte, 1),
+            (&b"\x80"[..], Utf8ErrorKind::UnexpectedContinuation, 1),
+        ];
+
+        for (input, kind, len) in test_cases {
+            match &utf8_segments(input)[0] {
+                Utf8Segment::Invalid(sequence) => {
+                    assert_eq!(sequence.kind, kind, "Failed for input: {:02X?}", input);
+                    assert_eq!(sequence.bytes.len(), len, "Failed for input: {:02X?}", input);
+                }
+                segment => panic!("Expected invalid sequence for {:02X?}, got {:?}", input, segment),
+            }
+        }
     }
 
     #[test]
-    fn test_dump_graphemes_json() {
-        let result = dump_graphemes("🍣", DumpFormat::Json).unwrap();
-        assert!(result.contains("cluster_index"));
-        assert!(result.contains("codepoints"));
-        assert!(result.contains("U+1F363"));
-        assert!(result.contains("SUSHI"));
+    fn test_utf8_segments_match_lossy_replacements() {
+        // Each invalid sequence corresponds to one U+FFFD from from_utf8_lossy
+        let bytes = b"\xF0\x9F\x8D \xF0\x9F\x8D\xA3 \xFF \xC0\x80";
+        let invalid = utf8_segments(bytes)
+            .iter()
+            .filter(|segment| matches!(segment, Utf8Segment::Invalid(_)))
+            .count();
+        assert_eq!(invalid, String::from_utf8_lossy(bytes).matches('\u{FFFD}').count());
     }
 
     #[test]
-    fn test_dump_graphemes_jsonl() {
-        let result = dump_graphemes("🍣🍺", DumpFormat::Jsonl).unwrap();
-        let lines: Vec<&str> = result.trim().split('\n').collect();
-        assert_eq!(lines.len(), 2);
-        assert!(lines[0].contains("🍣"));
-        assert!(lines[1].contains("🍺"));
+    fn test_dump_bytes_invalid_sequence() {
+        let result = dump_bytes(b"A\xF0\x9F\x8D\xE3\x81\x82", DumpFormat::Text).unwrap();
+        assert!(result.contains("Cluster 0: A (1 codepoint)  offset: byte 0, codepoint 0, utf-16 0"));
+        assert!(result.contains("Cluster 1: \\xF0\\x9F\\x8D (invalid)  offset: byte 1, codepoint 1, utf-16 1"));
+        assert!(result.contains("  bytes: F0 9F 8D  reason: truncated sequence"));
+        assert!(result.contains("Cluster 2: あ (1 codepoint)  offset: byte 4, codepoint 2, utf-16 2"));
     }
 
     #[test]
-    fn test_dump_complex_emoji() {
-        let result = dump_graphemes("👩‍👩‍👧‍👦", DumpFormat::Text).unwrap();
-        assert!(result.contains("Cluster 0: 👩‍👩‍👧‍👦 (7 codepoints)"));
-        assert!(result.contains("WOMAN"));
-        assert!(result.contains("ZERO WIDTH JOINER"));
-        assert!(result.contains("GIRL"));
-        assert!(result.contains("BOY"));
+    fn test_dump_bytes_json() {
+        let result = dump_bytes(b"\xC0\x80", DumpFormat::Jsonl).unwrap();
+        let lines: Vec<&str> = result.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("\"invalid\":{\"bytes\":\"C0\",\"kind\":\"overlong\",\"reason\":\"overlong encoding\"}"));
+        assert!(lines[1].contains("\"kind\":\"unexpected_continuation\""));
     }
 
     #[test]
-    fn test_get_unicode_name() {
-        assert_eq!(get_unicode_name('A'), "LATIN CAPITAL LETTER A");
-        assert_eq!(get_unicode_name('🍣'), "SUSHI");
-        assert_eq!(get_unicode_name('\u{200D}'), "ZERO WIDTH JOINER");
+    fn test_dump_bytes_valid_matches_dump_graphemes() {
+        let input = "あ🍣👨‍💻";
+        assert_eq!(
+            dump_bytes(input.as_bytes(), DumpFormat::Json).unwrap(),
+            dump_graphemes(input, DumpFormat::Json).unwrap()
+        );
     }
 
     #[test]
@@ -863,6 +3880,43 @@ mod tests {
         assert_eq!(result, "");
     }
 
+    #[test]
+    fn test_bytes_to_hex_language_literals() {
+        let bytes = "🍣".as_bytes();
+        let cases = [
+            (HexFormat::CArray, "{0xF0, 0x9F, 0x8D, 0xA3}"),
+            (HexFormat::Rust, "b\"\\xF0\\x9F\\x8D\\xA3\""),
+            (HexFormat::Python, "b\"\\xF0\\x9F\\x8D\\xA3\""),
+            (HexFormat::JavaScript, "new Uint8Array([0xF0, 0x9F, 0x8D, 0xA3])"),
+            (HexFormat::Go, "[]byte{0xF0, 0x9F, 0x8D, 0xA3}"),
+            (HexFormat::List, "0xF0, 0x9F, 0x8D, 0xA3"),
+        ];
+        for (format, expected) in cases {
+            assert_eq!(bytes_to_hex(bytes, false, format.clone(), None), expected, "{:?}", format);
+        }
+        assert_eq!(bytes_to_hex(bytes, true, HexFormat::CArray, None), "{0xf0, 0x9f, 0x8d, 0xa3}");
+    }
+
+    #[test]
+    fn test_bytes_to_hex_line_width() {
+        let bytes = "🍣あ".as_bytes();
+        assert_eq!(bytes_to_hex(bytes, false, HexFormat::Spaced, Some(4)), "F0 9F 8D A3\nE3 81 82");
+        assert_eq!(bytes_to_hex(bytes, false, HexFormat::CArray, Some(4)), "{\n    0xF0, 0x9F, 0x8D, 0xA3,\n    0xE3, 0x81, 0x82,\n}");
+        assert_eq!(bytes_to_hex(bytes, false, HexFormat::Go, Some(4)), "[]byte{\n\t0xF0, 0x9F, 0x8D, 0xA3,\n\t0xE3, 0x81, 0x82,\n}");
+        assert_eq!(bytes_to_hex(bytes, false, HexFormat::Rust, Some(4)), "b\"\\xF0\\x9F\\x8D\\xA3\\\n  \\xE3\\x81\\x82\"");
+        assert_eq!(bytes_to_hex(bytes, false, HexFormat::Python, Some(4)), "(\n    b\"\\xF0\\x9F\\x8D\\xA3\"\n    b\"\\xE3\\x81\\x82\"\n)");
+        assert_eq!(bytes_to_hex(bytes, false, HexFormat::List, Some(4)), "0xF0, 0x9F, 0x8D, 0xA3,\n0xE3, 0x81, 0x82");
+        // Input that fits on one line is not wrapped
+        assert_eq!(bytes_to_hex(bytes, false, HexFormat::JavaScript, Some(16)), "new Uint8Array([0xF0, 0x9F, 0x8D, 0xA3, 0xE3, 0x81, 0x82])");
+    }
+
+    #[test]
+    fn test_bytes_to_hex_empty_literals() {
+        assert_eq!(bytes_to_hex(b"", false, HexFormat::CArray, Some(8)), "{}");
+        assert_eq!(bytes_to_hex(b"", false, HexFormat::Rust, None), "b\"\"");
+        assert_eq!(bytes_to_hex(b"", false, HexFormat::JavaScript, None), "new Uint8Array([])");
+    }
+
     // Tests for hex2bin function
     #[test]
     fn test_hex2bin_default_format() {
@@ -918,6 +3972,319 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_hex2bin_modes_with_invalid_utf8() {
+        let hex = "41 F0 9F 8D 42";
+        assert!(hex2bin_with_mode(hex, HexParseMode::Lenient, DecodeMode::Strict).is_err());
+        assert_eq!(hex2bin_with_mode(hex, HexParseMode::Lenient, DecodeMode::Raw).unwrap(), b"A\xF0\x9F\x8DB");
+        assert_eq!(hex2bin_with_mode(hex, HexParseMode::Lenient, DecodeMode::Lossy).unwrap(), "A\u{FFFD}B".as_bytes());
+        assert_eq!(hex2bin_with_mode(hex, HexParseMode::Lenient, DecodeMode::Escaped).unwrap(), b"A\\xF0\\x9F\\x8DB");
+    }
+
+    #[test]
+    fn test_hex2bin_modes_with_valid_utf8() {
+        for mode in [DecodeMode::Strict, DecodeMode::Raw, DecodeMode::Lossy, DecodeMode::Escaped] {
+            assert_eq!(hex2bin_with_mode("F09F8DA3", HexParseMode::Lenient, mode).unwrap(), "🍣".as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_hexdump_utf8_text_column() {
+        let result = hexdump("あいうえおABC🍺".as_bytes(), false, false);
+        assert_eq!(result, "\
+00000000  E3 81 82 E3 81 84 E3 81  86 E3 81 88 E3 81 8A 41  |あ·い·う·え·お·A|
+00000010  42 43 F0 9F 8D BA                                 |BC🍺··|
+00000016
+");
+    }
+
+    #[test]
+    fn test_hexdump_wide_character_across_rows() {
+        let result = hexdump("ABCDEFGHIJKLMNOあ🍣".as_bytes(), false, false);
+        assert_eq!(result, "\
+00000000  41 42 43 44 45 46 47 48  49 4A 4B 4C 4D 4E 4F E3  |ABCDEFGHIJKLMNO·|
+00000010  81 82 F0 9F 8D A3                                 |あ🍣··|
+00000016
+");
+        // Text columns have the display width of the byte count
+        assert!(hexdump("ABCDEFGHIJKLMN🍣".as_bytes(), false, false).contains("|ABCDEFGHIJKLMN🍣|\n00000010  8D A3 "));
+    }
+
+    #[test]
+    fn test_hexdump_nonprintable_and_marks() {
+        let result = hexdump("a\n\u{301}\u{200D}".as_bytes(), true, false);
+        assert_eq!(result, "00000000  61 0a cc 81 e2 80 8d                              |a.◌\u{301}·.··|\n00000007\n");
+    }
+
+    #[test]
+    fn test_hexdump_invalid_bytes() {
+        let result = hexdump(b"A\xF0\x9F\x8DB", false, false);
+        assert!(result.starts_with("00000000  41 F0 9F 8D 42 "));
+        assert!(result.contains("|A\u{FFFD}\u{FFFD}\u{FFFD}B|"));
+        let colored = hexdump(b"A\xFF", false, true);
+        assert!(colored.contains("41 \x1b[1;31mFF\x1b[0m "));
+        assert!(colored.contains("|A\x1b[1;31m\u{FFFD}\x1b[0m|"));
+    }
+
+    #[test]
+    fn test_hexdump_empty() {
+        assert_eq!(hexdump(b"", false, false), "");
+    }
+
+    #[test]
+    fn test_base_encode_rfc4648_vectors() {
+        let cases = [("", "", ""), ("f", "Zg==", "MY======"), ("fo", "Zm8=", "MZXQ===="), ("foo", "Zm9v", "MZXW6==="), ("foobar", "Zm9vYmFy", "MZXW6YTBOI======")];
+        for (input, base64, base32) in cases {
+            assert_eq!(base_encode(input.as_bytes(), BaseEncoding::Base64, true), base64);
+            assert_eq!(base_encode(input.as_bytes(), BaseEncoding::Base32, true), base32);
+            assert_eq!(base_decode(base64, BaseEncoding::Base64).unwrap(), input.as_bytes());
+            assert_eq!(base_decode(base32, BaseEncoding::Base32).unwrap(), input.as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_base64url_without_padding() {
+        let bytes = [0xFB, 0xFF];
+        assert_eq!(base_encode(&bytes, BaseEncoding::Base64, true), "+/8=");
+        assert_eq!(base_encode(&bytes, BaseEncoding::Base64Url, false), "-_8");
+        assert_eq!(base_decode("-_8", BaseEncoding::Base64Url).unwrap(), bytes);
+        assert!(base_decode("-_8", BaseEncoding::Base64).is_err());
+    }
+
+    #[test]
+    fn test_base_decode_ignores_whitespace_and_case() {
+        let text = "日本語テキスト";
+        let encoded = base_encode(text.as_bytes(), BaseEncoding::Base64, true);
+        let wrapped = format!("{}\r\n{}\n", &encoded[..8], &encoded[8..]);
+        assert_eq!(base_decode(&wrapped, BaseEncoding::Base64).unwrap(), text.as_bytes());
+        assert_eq!(base_decode("mzxw6===", BaseEncoding::Base32).unwrap(), b"foo");
+    }
+
+    #[test]
+    fn test_base_decode_errors() {
+        let err = base_decode("Zm9v!", BaseEncoding::Base64).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid Base64 character '!' at position 4");
+        let err = base_decode("Zm9vY", BaseEncoding::Base64).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid Base64 input: truncated after 5 characters");
+        assert!(base_decode("MZX", BaseEncoding::Base32).is_err());
+    }
+
+    #[test]
+    fn test_ascii85() {
+        assert_eq!(base_encode(b"Hello World!", BaseEncoding::Ascii85, true), "87cURD]i,\"Ebo80");
+        assert_eq!(base_encode(&[0, 0, 0, 0, 0, 0], BaseEncoding::Ascii85, true), "z!!!");
+        assert_eq!(base_decode("<~87cURD]i,\"Ebo80~>", BaseEncoding::Ascii85).unwrap(), b"Hello World!");
+        assert_eq!(base_decode("z!!!", BaseEncoding::Ascii85).unwrap(), [0; 6]);
+        let text = "日本語テキスト🍣";
+        let encoded = base_encode(text.as_bytes(), BaseEncoding::Ascii85, true);
+        assert_eq!(base_decode(&encoded, BaseEncoding::Ascii85).unwrap(), text.as_bytes());
+        assert!(base_decode("s8W-\"", BaseEncoding::Ascii85).is_err());
+        assert!(base_decode("87cURD]i,\"Ebo80x", BaseEncoding::Ascii85).is_err());
+    }
+
+    #[test]
+    fn test_decode_output_modes() {
+        let bytes = vec![0x41, 0xFF];
+        assert!(decode_output(bytes.clone(), DecodeMode::Strict).is_err());
+        assert_eq!(decode_output(bytes.clone(), DecodeMode::Raw).unwrap(), b"A\xFF");
+        assert_eq!(decode_output(bytes.clone(), DecodeMode::Lossy).unwrap(), "A\u{FFFD}".as_bytes());
+        assert_eq!(decode_output(bytes, DecodeMode::Escaped).unwrap(), b"A\\xFF");
+    }
+
+    #[test]
+    fn test_url_encode_component_sets() {
+        let input = "/資料/a b?x=1&y=#\"<>@:+~%".as_bytes();
+        let cases = [
+            (UrlComponent::Path, "/%E8%B3%87%E6%96%99/a%20b%3Fx=1&y=%23%22%3C%3E@:+~%25"),
+            (UrlComponent::Query, "/%E8%B3%87%E6%96%99/a%20b?x=1&y=%23%22%3C%3E@:+~%25"),
+            (UrlComponent::Form, "%2F%E8%B3%87%E6%96%99%2Fa+b%3Fx%3D1%26y%3D%23%22%3C%3E%40%3A%2B%7E%25"),
+            (UrlComponent::Userinfo, "%2F%E8%B3%87%E6%96%99%2Fa%20b%3Fx%3D1&y%3D%23%22%3C%3E%40%3A+~%25"),
+            (UrlComponent::Fragment, "/%E8%B3%87%E6%96%99/a%20b?x=1&y=#%22%3C%3E@:+~%25"),
+            (UrlComponent::All, "%2F%E8%B3%87%E6%96%99%2Fa%20b%3Fx%3D1%26y%3D%23%22%3C%3E%40%3A%2B~%25"),
+        ];
+        for (component, expected) in cases {
+            assert_eq!(url_encode(input, component), expected, "{:?}", component);
+        }
+    }
+
+    #[test]
+    fn test_url_encode_controls_and_invalid_bytes() {
+        assert_eq!(url_encode(b"a\tb\x7F\xFF", UrlComponent::Fragment), "a%09b%7F%FF");
+    }
+
+    #[test]
+    fn test_url_decode() {
+        assert_eq!(url_decode("%E6%97%A5%e6%9c%ac+x", false), "日本+x".as_bytes());
+        assert_eq!(url_decode("%E6%97%A5%E6%9C%AC+x", true), "日本 x".as_bytes());
+        // Malformed escapes are kept as they are
+        assert_eq!(url_decode("100%+%zz%4", false), b"100%+%zz%4");
+        assert_eq!(url_decode("%E6%97", false), b"\xE6\x97");
+        // from_str_radix would take the sign as part of the number
+        assert_eq!(url_decode("%+A", false), b"%+A");
+        assert_eq!(url_decode("%-1", false), b"%-1");
+        assert_eq!(url_decode("%+A", true), b"% A");
+    }
+
+    #[test]
+    fn test_url_roundtrip() {
+        let input = "東京都 渋谷区?q=ラーメン&page=2";
+        for component in [UrlComponent::Path, UrlComponent::Query, UrlComponent::Userinfo, UrlComponent::Fragment, UrlComponent::All] {
+            assert_eq!(url_decode(&url_encode(input.as_bytes(), component), false), input.as_bytes());
+        }
+        assert_eq!(url_decode(&url_encode(input.as_bytes(), UrlComponent::Form), true), input.as_bytes());
+    }
+
+    #[test]
+    fn test_parse_hex_lenient_formats() {
+        let inputs = [
+            "F09F8DA3",
+            "F0 9F 8D A3",
+            "\\xF0\\x9F\\x8D\\xA3",
+            "F0 9F\\x8D\\xA3",
+            "0xF0,0x9F,0x8D,0xA3",
+            "{0xF0, 0x9F, 0x8D, 0xA3}",
+            "[0xf0, 0x9f, 0x8d, 0xa3]",
+            "%F0%9F%8D%A3",
+            "F0:9F:8D:A3",
+            "F09F\n8DA3\n",
+            "\"\\xF0\\x9F\\x8D\\xA3\"",
+        ];
+        for input in inputs {
+            assert_eq!(parse_hex(input, HexParseMode::Lenient).unwrap(), [0xF0, 0x9F, 0x8D, 0xA3], "{}", input);
+        }
+        assert_eq!(parse_hex("{0x0, 0xA}", HexParseMode::Lenient).unwrap(), [0x00, 0x0A]);
+    }
+
+    #[test]
+    fn test_parse_hex_reads_every_hex_format() {
+        let bytes = [0xF0, 0x9F, 0x8D, 0xA3, 0x00, 0x0A, 0x5C, 0x22, 0xBB];
+        let formats = [
+            HexFormat::Default,
+            HexFormat::Spaced,
+            HexFormat::Escaped,
+            HexFormat::CArray,
+            HexFormat::Rust,
+            HexFormat::Python,
+            HexFormat::JavaScript,
+            HexFormat::Go,
+            HexFormat::List,
+        ];
+        for format in formats {
+            for width in [None, Some(1), Some(4), Some(16)] {
+                for lowercase in [false, true] {
+                    let hex = bytes_to_hex(&bytes, lowercase, format.clone(), width);
+                    assert_eq!(parse_hex(&hex, HexParseMode::Lenient).unwrap(), bytes, "{:?} {:?}: {}", format, width, hex);
+                }
+            }
+            let empty = bytes_to_hex(b"", false, format.clone(), None);
+            assert_eq!(parse_hex(&empty, HexParseMode::Lenient).unwrap(), b"", "{:?}: {}", format, empty);
+        }
+        assert!(parse_hex("b", HexParseMode::Lenient).is_err());
+    }
+
+    #[test]
+    fn test_parse_hex_lenient_errors() {
+        let err = parse_hex("F0 9G 8 0x xyz", HexParseMode::Lenient).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid hex input:\n  \
+            offset 3: 9G (not a hex digit)\n  \
+            offset 6: 8 (odd number of hex digits)\n  \
+            offset 8: 0x (expected hex digits)\n  \
+            offset 11: xyz (not a hex digit)");
+    }
+
+    #[test]
+    fn test_parse_hex_strict() {
+        for input in ["F09F8DA3", " F0 9F 8D A3\n", "\\xF0\\x9F\\x8D\\xA3"] {
+            assert_eq!(parse_hex(input, HexParseMode::Strict).unwrap(), [0xF0, 0x9F, 0x8D, 0xA3]);
+        }
+        assert_eq!(parse_hex("F09F \\x8D\\xA3", HexParseMode::Strict).unwrap(), [0xF0, 0x9F, 0x8D, 0xA3]);
+        let err = parse_hex("F0 9F\\x8D 0xA3", HexParseMode::Strict).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid hex input:\n  \
+            offset 3: 9F\\x8D (not a hex digit)\n  \
+            offset 10: 0xA3 (prefix not allowed in strict mode)");
+        let err = parse_hex("\\xF0\\x9\\xGG", HexParseMode::Strict).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid hex input:\n  offset 0: \\xF0\\x9\\xGG (expected two hex digits after \\x)");
+        let err = parse_hex("F09F8DA", HexParseMode::Strict).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid hex input:\n  offset 0: F09F8DA (odd number of hex digits)");
+    }
+
+    #[test]
+    fn test_parse_hex_strict_reports_each_token_once() {
+        let err = parse_hex("0xF0,0x9F,0x8D,0xA3", HexParseMode::Strict).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid hex input:\n  \
+            offset 0: 0xF0 (prefix not allowed in strict mode)\n  \
+            offset 4: , (separator not allowed in strict mode)\n  \
+            offset 5: 0x9F (prefix not allowed in strict mode)\n  \
+            offset 9: , (separator not allowed in strict mode)\n  \
+            offset 10: 0x8D (prefix not allowed in strict mode)\n  \
+            offset 14: , (separator not allowed in strict mode)\n  \
+            offset 15: 0xA3 (prefix not allowed in strict mode)");
+        let err = parse_hex("{F0, 9F}", HexParseMode::Strict).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid hex input:\n  \
+            offset 0: { (separator not allowed in strict mode)\n  \
+            offset 3: , (separator not allowed in strict mode)\n  \
+            offset 7: } (separator not allowed in strict mode)");
+    }
+
+    #[test]
+    fn test_scrub_policies() {
+        let bytes = b"A\xF0\x9F\x8DB\xC0\x80C";
+        let cases: [(ScrubPolicy, &[u8], usize); 6] = [
+            (ScrubPolicy::Lossy, "A\u{FFFD}B\u{FFFD}\u{FFFD}C".as_bytes(), 3),
+            (ScrubPolicy::PerByte, "A\u{FFFD}\u{FFFD}\u{FFFD}B\u{FFFD}\u{FFFD}C".as_bytes(), 5),
+            (ScrubPolicy::Replace("?".to_string()), b"A?B??C", 3),
+            (ScrubPolicy::Delete, b"ABC", 3),
+            (ScrubPolicy::Escape, b"A\\xF0\\x9F\\x8DB\\xC0\\x80C", 5),
+            (ScrubPolicy::SurrogateEscape, b"A\xED\xB3\xB0\xED\xB2\x9F\xED\xB2\x8DB\xED\xB3\x80\xED\xB2\x80C", 5),
+        ];
+        for (policy, expected, replacements) in cases {
+            let result = scrub_bytes(bytes, &policy);
+            assert_eq!(result.bytes, expected, "{:?}", policy);
+            assert_eq!(result.replacements, replacements, "{:?}", policy);
+        }
+    }
+
+    #[test]
+    fn test_scrub_output_is_valid_utf8() {
+        let bytes = b"\xE3\x81\xFF\xED\xA0\x80\xF4\x90\x80\x80ok";
+        for policy in [ScrubPolicy::Lossy, ScrubPolicy::PerByte, ScrubPolicy::Delete, ScrubPolicy::Escape] {
+            assert!(validate_utf8(&scrub_bytes(bytes, &policy).bytes).is_empty(), "{:?}", policy);
+        }
+        // Surrogate escapes are encoded surrogates on purpose, one per invalid byte
+        let errors = validate_utf8(&scrub_bytes(b"A\x80\xFF", &ScrubPolicy::SurrogateEscape).bytes);
+        assert_eq!(errors.iter().map(|error| error.sequence.kind).collect::<Vec<_>>(), vec![Utf8ErrorKind::Surrogate; 2]);
+    }
+
+    #[test]
+    fn test_scrub_escape_is_reversible() {
+        let escaped = scrub_bytes(b"\\xFF", &ScrubPolicy::Escape);
+        let invalid = scrub_bytes(b"\xFF", &ScrubPolicy::Escape);
+        assert_eq!(escaped.bytes, b"\\\\xFF");
+        assert_eq!(escaped.replacements, 0);
+        assert_eq!(invalid.bytes, b"\\xFF");
+        assert_ne!(escaped.bytes, invalid.bytes);
+    }
+
+    #[test]
+    fn test_scrub_lossy_policy_matches_from_utf8_lossy() {
+        let bytes = b"\xE3\x81\xFF\xED\xA0\x80\xF4\x90\x80\x80ok";
+        let result = scrub_bytes(bytes, &ScrubPolicy::Lossy);
+        assert_eq!(result.bytes, String::from_utf8_lossy(bytes).as_bytes());
+        assert_eq!(scrub_invalid_utf8_with_policy("E381FF", InputFormat::Hex, &ScrubPolicy::Delete).unwrap().replacements, 2);
+    }
+
+    #[test]
+    fn test_scrub_valid_input_has_no_replacements() {
+        let result = scrub_bytes("日本語".as_bytes(), &ScrubPolicy::Escape);
+        assert_eq!(result, ScrubOutput { bytes: "日本語".as_bytes().to_vec(), replacements: 0 });
+    }
+
+    #[test]
+    fn test_scrub_lenient_hex_input() {
+        let result = scrub_invalid_utf8("{0xF0, 0x9F, 0x8D}", InputFormat::Hex).unwrap();
+        assert_eq!(result, "\u{FFFD}");
+    }
+
     // Roundtrip tests
     #[test]
     fn test_roundtrip_default() {
@@ -1045,6 +4412,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_validate_names_invalid_sequences() {
+        let test_cases = vec![
+            ("80", vec![(&[0x80][..], Utf8ErrorKind::UnexpectedContinuation)]),
+            ("C0", vec![(&[0xC0][..], Utf8ErrorKind::Overlong)]),
+            ("E0", vec![(&[0xE0], Utf8ErrorKind::Truncated)]),
+            ("F0", vec![(&[0xF0], Utf8ErrorKind::Truncated)]),
+            ("FE", vec![(&[0xFE], Utf8ErrorKind::InvalidByte)]),
+            ("FEFFF8", vec![(&[0xFE], Utf8ErrorKind::InvalidByte), (&[0xFF], Utf8ErrorKind::InvalidByte), (&[0xF8], Utf8ErrorKind::InvalidByte)]),
+            ("F88080", vec![(&[0xF8], Utf8ErrorKind::InvalidByte), (&[0x80], Utf8ErrorKind::UnexpectedContinuation), (&[0x80], Utf8ErrorKind::UnexpectedContinuation)]),
+            ("F7BFBFBF", vec![(&[0xF7, 0xBF, 0xBF, 0xBF], Utf8ErrorKind::OutOfRange)]),
+            ("C0C0", vec![(&[0xC0], Utf8ErrorKind::Overlong), (&[0xC0], Utf8ErrorKind::Overlong)]),
+            ("E080", vec![(&[0xE0, 0x80], Utf8ErrorKind::Overlong)]),
+            ("F08080", vec![(&[0xF0, 0x80, 0x80], Utf8ErrorKind::Overlong)]),
+            ("EDA080", vec![(&[0xED, 0xA0, 0x80], Utf8ErrorKind::Surrogate)]),
+            ("F4908080", vec![(&[0xF4, 0x90, 0x80, 0x80], Utf8ErrorKind::OutOfRange)]),
+            ("C08080", vec![(&[0xC0, 0x80], Utf8ErrorKind::Overlong), (&[0x80], Utf8ErrorKind::UnexpectedContinuation)]),
+        ];
+
+        for (input, expected) in test_cases {
+            let errors = validate_utf8(&input_to_bytes(input, InputFormat::Hex).unwrap());
+            let found: Vec<_> = errors.iter().map(|error| (&error.sequence.bytes[..], error.sequence.kind)).collect();
+            assert_eq!(found, expected, "Failed for input: {}", input);
+        }
+    }
+
+    #[test]
+    fn test_validate_lines_and_columns() {
+        let errors = validate_utf8(b"ok\n\xE3\x81\x82\xE3\x81 x\xC0\x80\n\xFF");
+        let positions: Vec<(usize, usize, usize)> = errors.iter().map(|error| (error.line, error.column, error.sequence.offset)).collect();
+        assert_eq!(positions, vec![(2, 2, 6), (2, 5, 10), (3, 1, 13)]);
+        assert_eq!(format_validation_error("data.txt", &errors[1]), "data.txt:2:5: overlong encoding: C0 80 (byte 10)");
+    }
+
+    #[test]
+    fn test_validate_valid_input() {
+        assert!(validate_utf8("日本語\n🍣".as_bytes()).is_empty());
+        assert!(validate_utf8(b"").is_empty());
+    }
+
     #[test]
     fn test_scrub_mixed_valid_invalid() {
         // Test mixing valid and invalid UTF-8
@@ -1120,6 +4527,34 @@ mod tests {
         assert_eq!(result, "ABC");
     }
 
+    #[test]
+    fn test_unescape_unicode_named() {
+        let result = unescape_unicode("\\N{HIRAGANA LETTER A}\\N{sushi}\\N{BYTE ORDER MARK}");
+        assert_eq!(result, "あ🍣\u{FEFF}");
+    }
+
+    #[test]
+    fn test_unescape_unicode_named_invalid() {
+        assert_eq!(unescape_unicode("\\N{NOT A NAME}x"), "\u{FFFD}x");
+        assert_eq!(unescape_unicode("\\N{}x"), "\u{FFFD}x");
+        assert_eq!(unescape_unicode("a\\N{SUSHI"), "a\u{FFFD}");
+    }
+
+    #[test]
+    fn test_escape_unicode_named() {
+        let result = escape_unicode_with_format("aあ🍣\u{7}\u{E000}", EscapeFormat::Named);
+        assert_eq!(
+            result,
+            "\\N{LATIN SMALL LETTER A}\\N{HIRAGANA LETTER A}\\N{SUSHI}\\u{7}\\u{E000}"
+        );
+    }
+
+    #[test]
+    fn test_escape_unicode_named_roundtrip() {
+        let input = "Hello, 世界 👨‍💻\u{7}한";
+        assert_eq!(unescape_unicode(&escape_unicode_with_format(input, EscapeFormat::Named)), input);
+    }
+
     #[test]
     fn test_unescape_unicode_surrogate_pair_valid() {
         // Valid surrogate pair for 🍣 (U+1F363)
@@ -1,6 +1,8 @@
 use anyhow::Result;
+use std::io::{IsTerminal, Read, Write};
+use std::path::PathBuf;
 use clap::{Parser, Subcommand, ValueEnum};
-use mojibox::{iter_byte, iter_codepoint, iter_grapheme_icu4x, count_units, take_units, drop_units, ProcessingMode as LibProcessingMode, dump_graphemes, DumpFormat, ord_characters, chr_from_codepoints, bin2hex, hex2bin, HexFormat as LibHexFormat, scrub_invalid_utf8, InputFormat as LibInputFormat, escape_unicode_with_format, unescape_unicode, EscapeFormat as LibEscapeFormat};
+use mojibox::{iter_byte, iter_codepoint, iter_grapheme_icu4x, count_units, take_units, drop_units, ProcessingMode as LibProcessingMode, dump_bytes_with_options, DumpFilter, DumpFormat, DumpOptions, ord_characters_with_notation, ord_graphemes, explain_encoding, OrdNotation as LibOrdNotation, chr_from_codepoints_with_options, bytes_to_hex, hex2bin_with_mode, HexParseMode, hexdump, DecodeMode as LibDecodeMode, decode_output, base_encode, base_decode, url_encode, url_decode, UrlComponent as LibUrlComponent, BaseEncoding as LibBaseEncoding, HexFormat as LibHexFormat, scrub_bytes, validate_utf8, format_validation_error, ScrubPolicy as LibScrubPolicy, input_to_bytes, InputFormat as LibInputFormat, escape_unicode_with_format, unescape_unicode, EscapeFormat as LibEscapeFormat, undump_bytes, find_chars_by_name, fuzzy_find_chars_by_name, format_codepoint_lines, chart_chars, format_chart, ChartFormat as LibChartFormat};
 
 #[derive(Parser)]
 #[command(name = "mojibox")]
@@ -77,25 +79,86 @@ enum Commands {
         #[arg(short, long, default_value = "text")]
         format: OutputFormat,
 
-        /// Input string to process
-        input: String,
+        /// Omit the header row in CSV and TSV output
+        #[arg(long)]
+        no_header: bool,
+
+        /// Language tag for the lang attribute of HTML output
+        #[arg(long)]
+        lang: Option<String>,
+
+        /// Highlight special characters in text output
+        #[arg(long, default_value = "auto")]
+        color: ColorMode,
+
+        /// Show Mandarin reading, JIS X 0208 row-cell and Shift_JIS bytes of CJK ideographs
+        #[arg(long)]
+        unihan: bool,
+
+        /// Show only clusters containing non-ASCII characters
+        #[arg(long)]
+        non_ascii: bool,
+
+        /// Show only clusters of more than one code point
+        #[arg(long)]
+        multi_codepoint: bool,
+
+        /// Show only clusters containing invisible or format characters
+        #[arg(long)]
+        invisible: bool,
+
+        /// Show only clusters containing a character of this general category (e.g. Cf, Format, L)
+        #[arg(long)]
+        category: Option<String>,
+
+        /// Show only clusters containing a character of this script (e.g. Cyrl, Cyrillic)
+        #[arg(long)]
+        script: Option<String>,
+
+        /// Input format (hex input may contain invalid UTF-8)
+        #[arg(long, default_value = "binary")]
+        input_format: InputFormat,
+
+        /// Read input from a file instead of the argument ("-" for stdin)
+        #[arg(long, conflicts_with = "input")]
+        file: Option<PathBuf>,
+
+        /// Input string to process (reads stdin if omitted)
+        input: Option<String>,
     },
     /// Convert characters to Unicode codepoints
     Ord {
+        /// Notation for each code point
+        #[arg(short = 'n', long, value_enum, default_value = "hex")]
+        notation: OrdNotation,
+
         /// Use lowercase hex format
         #[arg(long)]
         lower: bool,
         
-        /// Output without 0x prefix
+        /// Output without 0x prefix (0o for octal)
         #[arg(long)]
         no_0x: bool,
+
+        /// Print one line per grapheme cluster
+        #[arg(long)]
+        grapheme: bool,
         
         /// Input string to process
         input: String,
     },
+    /// Show how each character's code point bits are encoded in UTF-8 and UTF-16
+    ExplainEncoding {
+        /// Input string to process
+        input: String,
+    },
     /// Convert Unicode codepoints to characters  
     Chr {
-        /// Unicode codepoints in hex format (with or without 0x prefix)
+        /// Leave unassigned code points out of ranges
+        #[arg(long)]
+        skip_unassigned: bool,
+
+        /// Code points (U+3042, 0x3042, 3042, #12354, &#12354;, &#x3042;, \u{3042}) or ranges (U+3041..U+3096)
         codepoints: Vec<String>,
     },
     /// Convert string to hexadecimal representation
@@ -107,23 +170,165 @@ enum Commands {
         /// Output format
         #[arg(short, long, default_value = "default")]
         format: HexFormat,
+
+        /// Number of bytes per line (no line breaks if omitted)
+        #[arg(short, long)]
+        width: Option<usize>,
         
         /// Input string to process
         input: String,
     },
     /// Convert hexadecimal representation to string
     Hex2bin {
+        /// How to output the decoded bytes
+        #[arg(short, long, value_enum, default_value = "strict")]
+        mode: DecodeMode,
+
+        /// Write the output to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Which hex notations to accept
+        #[arg(short, long, value_enum, default_value = "lenient")]
+        parse: HexParse,
+
         /// Hexadecimal input (supports various formats)
         hex_input: String,
     },
+    /// Encode text or bytes as Base64, Base32 or Ascii85
+    Encode {
+        /// Encoding to use
+        #[arg(short, long, value_enum, default_value = "base64")]
+        encoding: BaseEncoding,
+
+        /// Omit the trailing "=" padding
+        #[arg(long)]
+        no_pad: bool,
+
+        /// Input format (hex input may contain invalid UTF-8)
+        #[arg(long, default_value = "binary")]
+        input_format: InputFormat,
+
+        /// Read input from a file instead of the argument ("-" for stdin)
+        #[arg(long, conflicts_with = "input")]
+        file: Option<PathBuf>,
+
+        /// Input string to process (reads stdin if omitted)
+        input: Option<String>,
+    },
+    /// Decode Base64, Base32 or Ascii85
+    Decode {
+        /// Encoding of the input
+        #[arg(short, long, value_enum, default_value = "base64")]
+        encoding: BaseEncoding,
+
+        /// How to output the decoded bytes
+        #[arg(short, long, value_enum, default_value = "lossy")]
+        mode: DecodeMode,
+
+        /// Write the output to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Read input from a file instead of the argument ("-" for stdin)
+        #[arg(long, conflicts_with = "input")]
+        file: Option<PathBuf>,
+
+        /// Encoded input (reads stdin if omitted)
+        input: Option<String>,
+    },
+    /// Percent-encode text or bytes for use in a URL
+    Urlencode {
+        /// Which characters to encode
+        #[arg(short, long, value_enum, default_value = "all")]
+        set: UrlComponent,
+
+        /// Input format (hex input may contain invalid UTF-8)
+        #[arg(long, default_value = "binary")]
+        input_format: InputFormat,
+
+        /// Read input from a file instead of the argument ("-" for stdin)
+        #[arg(long, conflicts_with = "input")]
+        file: Option<PathBuf>,
+
+        /// Input string to process (reads stdin if omitted)
+        input: Option<String>,
+    },
+    /// Decode percent-encoded text
+    Urldecode {
+        /// Decode "+" as a space (application/x-www-form-urlencoded)
+        #[arg(long)]
+        form: bool,
+
+        /// How to output the decoded bytes
+        #[arg(short, long, value_enum, default_value = "lossy")]
+        mode: DecodeMode,
+
+        /// Write the output to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Read input from a file instead of the argument ("-" for stdin)
+        #[arg(long, conflicts_with = "input")]
+        file: Option<PathBuf>,
+
+        /// Percent-encoded input (reads stdin if omitted)
+        input: Option<String>,
+    },
+    /// Show bytes as offset, hex and text columns like hexdump -C, aligned to UTF-8 characters
+    Hexdump {
+        /// Use lowercase hex format
+        #[arg(long)]
+        lower: bool,
+
+        /// Highlight invalid UTF-8 bytes
+        #[arg(long, default_value = "auto")]
+        color: ColorMode,
+
+        /// Input format (hex input may contain invalid UTF-8)
+        #[arg(long, default_value = "binary")]
+        input_format: InputFormat,
+
+        /// Read input from a file instead of the argument ("-" for stdin)
+        #[arg(long, conflicts_with = "input")]
+        file: Option<PathBuf>,
+
+        /// Input string to process (reads stdin if omitted)
+        input: Option<String>,
+    },
+    /// Check that input is well-formed UTF-8, exiting with status 1 if it is not
+    Validate {
+        /// Input format
+        #[arg(long, default_value = "binary")]
+        input_format: InputFormat,
+
+        /// Read input from a file instead of the argument ("-" for stdin)
+        #[arg(long, conflicts_with = "input")]
+        file: Option<PathBuf>,
+
+        /// Input data to validate (reads stdin if omitted)
+        input: Option<String>,
+    },
     /// Replace invalid UTF-8 sequences with replacement character (U+FFFD)
     Scrub {
         /// Input format
         #[arg(long, default_value = "binary")]
         input_format: InputFormat,
+
+        /// What to put in place of invalid UTF-8
+        #[arg(short, long, value_enum, default_value = "lossy")]
+        policy: ScrubPolicy,
+
+        /// Replacement string for the replace policy
+        #[arg(long, required_if_eq("policy", "replace"))]
+        replacement: Option<String>,
+
+        /// Read input from a file instead of the argument ("-" for stdin)
+        #[arg(long, conflicts_with = "input")]
+        file: Option<PathBuf>,
         
-        /// Input data to scrub
-        input: String,
+        /// Input data to scrub (reads stdin if omitted)
+        input: Option<String>,
     },
     /// Escape string to Unicode escape sequences
     Escape {
@@ -139,6 +344,41 @@ enum Commands {
         /// Input string with escape sequences to unescape
         input: String,
     },
+    /// Find characters by Unicode name (exact, wildcard, substring or fuzzy)
+    Name {
+        /// Rank characters by name similarity instead of exact/wildcard/substring search
+        #[arg(long)]
+        fuzzy: bool,
+
+        /// Maximum number of characters to show
+        #[arg(short, long, default_value = "50")]
+        limit: usize,
+
+        /// Name, alias, wildcard pattern ("HIRAGANA LETTER SMALL *") or part of a name
+        query: String,
+    },
+    /// List the assigned characters in a Unicode block or code point range
+    Chart {
+        /// Output format
+        #[arg(short, long, default_value = "grid")]
+        format: ChartFormat,
+
+        /// Show only characters of this general category (e.g. Lo, Letter, P)
+        #[arg(long)]
+        category: Option<String>,
+
+        /// Block name ("Katakana") or code point range ("U+30A0..U+30FF", "30A0-30FF")
+        block_or_range: String,
+    },
+    /// Rebuild the original string from dump JSON or JSON Lines output
+    Undump {
+        /// Read the dump from a file instead of the argument ("-" for stdin)
+        #[arg(long, conflicts_with = "input")]
+        file: Option<PathBuf>,
+
+        /// Dump JSON (reads from stdin if omitted)
+        input: Option<String>,
+    },
 }
 
 #[derive(ValueEnum, Clone, Debug)]
@@ -167,6 +407,24 @@ enum OutputFormat {
     Json,
     /// JSON Lines format
     Jsonl,
+    /// Comma-separated values, one row per codepoint
+    Csv,
+    /// Tab-separated values, one row per codepoint
+    Tsv,
+    /// Markdown table
+    Markdown,
+    /// HTML table
+    Html,
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+enum ColorMode {
+    /// Colorize when stdout is a terminal
+    Auto,
+    /// Always colorize
+    Always,
+    /// Never colorize
+    Never,
 }
 
 #[derive(ValueEnum, Clone, Debug)]
@@ -177,6 +435,83 @@ enum HexFormat {
     Spaced,
     /// Escaped format with \x prefix
     Escaped,
+    /// C array initializer ({0xF0, 0x9F})
+    C,
+    /// Rust byte string (b"\xF0\x9F")
+    Rust,
+    /// Python bytes literal (b"\xF0\x9F")
+    Python,
+    /// JavaScript Uint8Array (new Uint8Array([0xF0, 0x9F]))
+    Javascript,
+    /// Go byte slice ([]byte{0xF0, 0x9F})
+    Go,
+    /// Comma-separated list with 0x prefix (0xF0, 0x9F)
+    List,
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+enum DecodeMode {
+    /// Decode as UTF-8 text, failing on invalid sequences
+    Strict,
+    /// Write the exact bytes, without a trailing newline
+    Raw,
+    /// Replace invalid UTF-8 sequences with U+FFFD
+    Lossy,
+    /// Write invalid UTF-8 bytes as \xHH
+    Escaped,
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+enum HexParse {
+    /// Any mix of 0x, \x and % prefixes and punctuation separators
+    Lenient,
+    /// Only continuous hex digits or \x escapes, separated by whitespace
+    Strict,
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+enum BaseEncoding {
+    /// Base64 with the standard alphabet (RFC 4648)
+    Base64,
+    /// Base64 with the URL and filename safe alphabet, as used by JWT
+    #[value(name = "base64url")]
+    Base64Url,
+    /// Base32 (RFC 4648)
+    Base32,
+    /// Ascii85, as used by PostScript and PDF
+    Ascii85,
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+enum UrlComponent {
+    /// Path segments
+    Path,
+    /// Query strings
+    Query,
+    /// Form data (application/x-www-form-urlencoded), with "+" for spaces
+    Form,
+    /// User names and passwords
+    Userinfo,
+    /// Fragments
+    Fragment,
+    /// Everything except unreserved characters (A-Z a-z 0-9 - . _ ~)
+    All,
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+enum ScrubPolicy {
+    /// One U+FFFD per invalid sequence
+    Lossy,
+    /// One U+FFFD per invalid byte
+    PerByte,
+    /// The string given with --replacement, once per invalid sequence
+    Replace,
+    /// Remove invalid sequences
+    Delete,
+    /// Write invalid bytes as \xNN and backslashes as \\
+    Escape,
+    /// Map invalid bytes to U+DC80..U+DCFF like Python's surrogateescape (output is not valid UTF-8)
+    SurrogateEscape,
 }
 
 #[derive(ValueEnum, Clone, Debug)]
@@ -187,12 +522,48 @@ enum InputFormat {
     Hex,
 }
 
+#[derive(ValueEnum, Clone, Debug)]
+enum ChartFormat {
+    /// Rows of 16 code points, like the Unicode code charts
+    Grid,
+    /// One line per character, as in dump text output
+    Lines,
+    /// JSON array of code points with their properties
+    Json,
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+enum OrdNotation {
+    /// Hexadecimal with 0x prefix (0x6F22)
+    Hex,
+    /// Unicode notation (U+6F22)
+    Unicode,
+    /// Decimal (28450)
+    Decimal,
+    /// Octal with 0o prefix (0o67442)
+    Octal,
+    /// HTML decimal entity (&#28450;)
+    HtmlDecimal,
+    /// HTML hex entity (&#x6F22;)
+    HtmlHex,
+    /// CSS escape (\6F22)
+    Css,
+    /// \u{6F22} escape
+    Escape,
+    /// JSON-compatible \u6F22 escape (with surrogate pairs)
+    Json,
+    /// UTF-8 bytes of each character ([E6 BC A2])
+    Utf8,
+}
+
 #[derive(ValueEnum, Clone, Debug)]
 enum EscapeFormat {
     /// Default \u{XXXX} format
     Default,
     /// JSON-compatible \uXXXX format (with surrogate pairs)
     Json,
+    /// \N{NAME} format, \u{XXXX} for characters without a name
+    Named,
 }
 
 fn main() -> Result<()> {
@@ -229,23 +600,72 @@ fn main() -> Result<()> {
         } => {
             handle_drop(mode, engine, n, input)?;
         }
-        Commands::Dump { format, input } => {
-            handle_dump(format, input)?;
+        Commands::Dump {
+            format,
+            no_header,
+            lang,
+            color,
+            unihan,
+            non_ascii,
+            multi_codepoint,
+            invisible,
+            category,
+            script,
+            input_format,
+            file,
+            input,
+        } => {
+            let bytes = read_input_bytes(input, file, input_format)?;
+            let filter = DumpFilter { non_ascii, multi_codepoint, invisible, category, script };
+            handle_dump(format, no_header, lang, color, unihan, filter, bytes)?;
+        }
+        Commands::Ord { notation, lower, no_0x, grapheme, input } => {
+            handle_ord(notation, lower, no_0x, grapheme, input)?;
         }
-        Commands::Ord { lower, no_0x, input } => {
-            handle_ord(lower, no_0x, input)?;
+        Commands::ExplainEncoding { input } => {
+            print!("{}", explain_encoding(&input));
         }
-        Commands::Chr { codepoints } => {
-            handle_chr(codepoints)?;
+        Commands::Chr { skip_unassigned, codepoints } => {
+            handle_chr(skip_unassigned, codepoints)?;
         }
-        Commands::Bin2hex { lower, format, input } => {
-            handle_bin2hex(lower, format, input)?;
+        Commands::Bin2hex { lower, format, width, input } => {
+            handle_bin2hex(lower, format, width, input)?;
         }
-        Commands::Hex2bin { hex_input } => {
-            handle_hex2bin(hex_input)?;
+        Commands::Hex2bin { mode, output, parse, hex_input } => {
+            handle_hex2bin(mode, output, parse, hex_input)?;
         }
-        Commands::Scrub { input_format, input } => {
-            handle_scrub(input_format, input)?;
+        Commands::Encode { encoding, no_pad, input_format, file, input } => {
+            let bytes = read_input_bytes(input, file, input_format)?;
+            handle_encode(encoding, no_pad, bytes)?;
+        }
+        Commands::Decode { encoding, mode, output, file, input } => {
+            let bytes = read_input_bytes(input, file, InputFormat::Binary)?;
+            handle_decode(encoding, mode, output, bytes)?;
+        }
+        Commands::Urlencode { set, input_format, file, input } => {
+            let bytes = read_input_bytes(input, file, input_format)?;
+            handle_urlencode(set, bytes)?;
+        }
+        Commands::Urldecode { form, mode, output, file, input } => {
+            let bytes = read_input_bytes(input, file, InputFormat::Binary)?;
+            handle_urldecode(form, mode, output, bytes)?;
+        }
+        Commands::Hexdump { lower, color, input_format, file, input } => {
+            let bytes = read_input_bytes(input, file, input_format)?;
+            handle_hexdump(lower, color, bytes)?;
+        }
+        Commands::Validate { input_format, file, input } => {
+            let source = match (&input, &file) {
+                (Some(_), _) => "<input>".to_string(),
+                (None, Some(path)) if path.as_os_str() != "-" => path.display().to_string(),
+                _ => "<stdin>".to_string(),
+            };
+            let bytes = read_input_bytes(input, file, input_format)?;
+            handle_validate(&source, bytes)?;
+        }
+        Commands::Scrub { input_format, policy, replacement, file, input } => {
+            let bytes = read_input_bytes(input, file, input_format)?;
+            handle_scrub(policy, replacement, bytes)?;
         }
         Commands::Escape { format, input } => {
             handle_escape(format, input)?;
@@ -253,6 +673,16 @@ fn main() -> Result<()> {
         Commands::Unescape { input } => {
             handle_unescape(input)?;
         }
+        Commands::Name { fuzzy, limit, query } => {
+            handle_name(fuzzy, limit, query)?;
+        }
+        Commands::Chart { format, category, block_or_range } => {
+            handle_chart(format, category, block_or_range)?;
+        }
+        Commands::Undump { file, input } => {
+            let bytes = read_input_bytes(input, file, InputFormat::Binary)?;
+            handle_undump(bytes)?;
+        }
     }
 
     Ok(())
@@ -266,11 +696,23 @@ fn convert_mode(mode: ProcessingMode) -> LibProcessingMode {
     }
 }
 
+fn use_color(color: ColorMode) -> bool {
+    match color {
+        ColorMode::Auto => std::io::stdout().is_terminal(),
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+    }
+}
+
 fn convert_format(format: OutputFormat) -> DumpFormat {
     match format {
         OutputFormat::Text => DumpFormat::Text,
         OutputFormat::Json => DumpFormat::Json,
         OutputFormat::Jsonl => DumpFormat::Jsonl,
+        OutputFormat::Csv => DumpFormat::Csv,
+        OutputFormat::Tsv => DumpFormat::Tsv,
+        OutputFormat::Markdown => DumpFormat::Markdown,
+        OutputFormat::Html => DumpFormat::Html,
     }
 }
 
@@ -332,49 +774,206 @@ fn handle_drop(mode: ProcessingMode, _engine: Engine, n: usize, input: String) -
     Ok(())
 }
 
-fn handle_dump(format: OutputFormat, input: String) -> Result<()> {
+fn read_input_bytes(input: Option<String>, file: Option<PathBuf>, format: InputFormat) -> Result<Vec<u8>> {
+    let raw = match (input, file) {
+        (Some(input), _) => input.into_bytes(),
+        (None, Some(path)) if path.as_os_str() != "-" => std::fs::read(&path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?,
+        _ => {
+            let mut buffer = Vec::new();
+            std::io::stdin().read_to_end(&mut buffer)?;
+            buffer
+        }
+    };
+
+    match format {
+        InputFormat::Binary => Ok(raw),
+        InputFormat::Hex => {
+            let hex = String::from_utf8(raw)
+                .map_err(|_| anyhow::anyhow!("Hex input is not valid UTF-8 text"))?;
+            input_to_bytes(hex.trim(), LibInputFormat::Hex)
+        }
+    }
+}
+
+fn handle_dump(
+    format: OutputFormat,
+    no_header: bool,
+    lang: Option<String>,
+    color: ColorMode,
+    unihan: bool,
+    filter: DumpFilter,
+    input: Vec<u8>,
+) -> Result<()> {
     let dump_format = convert_format(format);
-    let output = dump_graphemes(&input, dump_format)?;
+    let options = DumpOptions { header: !no_header, lang, color: use_color(color), unihan, filter };
+    let output = dump_bytes_with_options(&input, dump_format, &options)?;
     print!("{}", output);
     Ok(())
 }
 
-fn handle_ord(lower: bool, no_0x: bool, input: String) -> Result<()> {
-    let codepoints = ord_characters(&input, lower, no_0x);
-    println!("{}", codepoints.join(" "));
+fn handle_ord(notation: OrdNotation, lower: bool, no_0x: bool, grapheme: bool, input: String) -> Result<()> {
+    let lib_notation = match notation {
+        OrdNotation::Hex => LibOrdNotation::Hex,
+        OrdNotation::Unicode => LibOrdNotation::Unicode,
+        OrdNotation::Decimal => LibOrdNotation::Decimal,
+        OrdNotation::Octal => LibOrdNotation::Octal,
+        OrdNotation::HtmlDecimal => LibOrdNotation::HtmlDecimal,
+        OrdNotation::HtmlHex => LibOrdNotation::HtmlHex,
+        OrdNotation::Css => LibOrdNotation::Css,
+        OrdNotation::Escape => LibOrdNotation::Escape,
+        OrdNotation::Json => LibOrdNotation::Json,
+        OrdNotation::Utf8 => LibOrdNotation::Utf8,
+    };
+    if grapheme {
+        for (cluster, values) in ord_graphemes(&input, lib_notation, lower, no_0x)? {
+            println!("{}\t{}", cluster, values.join(" "));
+        }
+    } else {
+        let codepoints = ord_characters_with_notation(&input, lib_notation, lower, no_0x);
+        println!("{}", codepoints.join(" "));
+    }
     Ok(())
 }
 
-fn handle_chr(codepoints: Vec<String>) -> Result<()> {
-    let result = chr_from_codepoints(&codepoints)?;
+fn handle_chr(skip_unassigned: bool, codepoints: Vec<String>) -> Result<()> {
+    let result = chr_from_codepoints_with_options(&codepoints, skip_unassigned)?;
     println!("{}", result);
     Ok(())
 }
 
-fn handle_bin2hex(lower: bool, format: HexFormat, input: String) -> Result<()> {
+fn handle_bin2hex(lower: bool, format: HexFormat, width: Option<usize>, input: String) -> Result<()> {
     let lib_format = match format {
         HexFormat::Default => LibHexFormat::Default,
         HexFormat::Spaced => LibHexFormat::Spaced,
         HexFormat::Escaped => LibHexFormat::Escaped,
+        HexFormat::C => LibHexFormat::CArray,
+        HexFormat::Rust => LibHexFormat::Rust,
+        HexFormat::Python => LibHexFormat::Python,
+        HexFormat::Javascript => LibHexFormat::JavaScript,
+        HexFormat::Go => LibHexFormat::Go,
+        HexFormat::List => LibHexFormat::List,
     };
-    let result = bin2hex(&input, lower, lib_format)?;
+    let result = bytes_to_hex(input.as_bytes(), lower, lib_format, width);
     println!("{}", result);
     Ok(())
 }
 
-fn handle_hex2bin(hex_input: String) -> Result<()> {
-    let result = hex2bin(&hex_input)?;
-    println!("{}", result);
+fn handle_hex2bin(mode: DecodeMode, output: Option<PathBuf>, parse: HexParse, hex_input: String) -> Result<()> {
+    let raw = matches!(mode, DecodeMode::Raw);
+    let parse = match parse {
+        HexParse::Lenient => HexParseMode::Lenient,
+        HexParse::Strict => HexParseMode::Strict,
+    };
+    let result = hex2bin_with_mode(&hex_input, parse, convert_decode_mode(mode))?;
+    write_decoded(result, raw, output)
+}
+
+fn convert_decode_mode(mode: DecodeMode) -> LibDecodeMode {
+    match mode {
+        DecodeMode::Strict => LibDecodeMode::Strict,
+        DecodeMode::Raw => LibDecodeMode::Raw,
+        DecodeMode::Lossy => LibDecodeMode::Lossy,
+        DecodeMode::Escaped => LibDecodeMode::Escaped,
+    }
+}
+
+fn write_decoded(mut result: Vec<u8>, raw: bool, output: Option<PathBuf>) -> Result<()> {
+    match output {
+        // Files always get the exact output, without a trailing newline
+        Some(path) => std::fs::write(&path, &result)
+            .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", path.display(), e))?,
+        None => {
+            if !raw {
+                result.push(b'\n');
+            }
+            std::io::stdout().write_all(&result)?;
+        }
+    }
+    Ok(())
+}
+
+fn convert_base_encoding(encoding: BaseEncoding) -> LibBaseEncoding {
+    match encoding {
+        BaseEncoding::Base64 => LibBaseEncoding::Base64,
+        BaseEncoding::Base64Url => LibBaseEncoding::Base64Url,
+        BaseEncoding::Base32 => LibBaseEncoding::Base32,
+        BaseEncoding::Ascii85 => LibBaseEncoding::Ascii85,
+    }
+}
+
+fn handle_encode(encoding: BaseEncoding, no_pad: bool, input: Vec<u8>) -> Result<()> {
+    println!("{}", base_encode(&input, convert_base_encoding(encoding), !no_pad));
     Ok(())
 }
 
-fn handle_scrub(input_format: InputFormat, input: String) -> Result<()> {
-    let lib_format = match input_format {
-        InputFormat::Binary => LibInputFormat::Binary,
-        InputFormat::Hex => LibInputFormat::Hex,
+fn handle_decode(encoding: BaseEncoding, mode: DecodeMode, output: Option<PathBuf>, input: Vec<u8>) -> Result<()> {
+    let text = String::from_utf8(input)
+        .map_err(|_| anyhow::anyhow!("Encoded input is not valid UTF-8 text"))?;
+    let raw = matches!(mode, DecodeMode::Raw);
+    let bytes = base_decode(&text, convert_base_encoding(encoding))?;
+    write_decoded(decode_output(bytes, convert_decode_mode(mode))?, raw, output)
+}
+
+fn handle_urlencode(set: UrlComponent, input: Vec<u8>) -> Result<()> {
+    let component = match set {
+        UrlComponent::Path => LibUrlComponent::Path,
+        UrlComponent::Query => LibUrlComponent::Query,
+        UrlComponent::Form => LibUrlComponent::Form,
+        UrlComponent::Userinfo => LibUrlComponent::Userinfo,
+        UrlComponent::Fragment => LibUrlComponent::Fragment,
+        UrlComponent::All => LibUrlComponent::All,
     };
-    let result = scrub_invalid_utf8(&input, lib_format)?;
-    println!("{}", result);
+    println!("{}", url_encode(&input, component));
+    Ok(())
+}
+
+fn handle_urldecode(form: bool, mode: DecodeMode, output: Option<PathBuf>, input: Vec<u8>) -> Result<()> {
+    let text = String::from_utf8(input)
+        .map_err(|_| anyhow::anyhow!("Encoded input is not valid UTF-8 text"))?;
+    let raw = matches!(mode, DecodeMode::Raw);
+    // A final newline from stdin is not part of the encoded text
+    let bytes = url_decode(text.trim_end_matches(['\r', '\n']), form);
+    write_decoded(decode_output(bytes, convert_decode_mode(mode))?, raw, output)
+}
+
+fn handle_hexdump(lower: bool, color: ColorMode, input: Vec<u8>) -> Result<()> {
+    print!("{}", hexdump(&input, lower, use_color(color)));
+    Ok(())
+}
+
+fn handle_validate(source: &str, input: Vec<u8>) -> Result<()> {
+    let errors = validate_utf8(&input);
+    if errors.is_empty() {
+        println!("{}: valid UTF-8 ({} bytes)", source, input.len());
+        return Ok(());
+    }
+    for error in &errors {
+        println!("{}", format_validation_error(source, error));
+    }
+    let plural = if errors.len() == 1 { "" } else { "s" };
+    Err(anyhow::anyhow!("{}: invalid UTF-8 ({} error{})", source, errors.len(), plural))
+}
+
+fn handle_scrub(policy: ScrubPolicy, replacement: Option<String>, input: Vec<u8>) -> Result<()> {
+    let lib_policy = match policy {
+        ScrubPolicy::Lossy => LibScrubPolicy::Lossy,
+        ScrubPolicy::PerByte => LibScrubPolicy::PerByte,
+        ScrubPolicy::Replace => LibScrubPolicy::Replace(replacement.unwrap_or_default()),
+        ScrubPolicy::Delete => LibScrubPolicy::Delete,
+        ScrubPolicy::Escape => LibScrubPolicy::Escape,
+        ScrubPolicy::SurrogateEscape => LibScrubPolicy::SurrogateEscape,
+    };
+    let mut result = scrub_bytes(&input, &lib_policy);
+    // Input read from a file or stdin usually ends with its own newline
+    if !result.bytes.ends_with(b"\n") {
+        result.bytes.push(b'\n');
+    }
+    std::io::stdout().write_all(&result.bytes)?;
+    if result.replacements > 0 {
+        let plural = if result.replacements == 1 { "" } else { "s" };
+        eprintln!("{} replacement{} made", result.replacements, plural);
+    }
     Ok(())
 }
 
@@ -382,6 +981,7 @@ fn handle_escape(format: EscapeFormat, input: String) -> Result<()> {
     let lib_format = match format {
         EscapeFormat::Default => LibEscapeFormat::Default,
         EscapeFormat::Json => LibEscapeFormat::Json,
+        EscapeFormat::Named => LibEscapeFormat::Named,
     };
     let result = escape_unicode_with_format(&input, lib_format);
     println!("{}", result);
@@ -393,3 +993,40 @@ fn handle_unescape(input: String) -> Result<()> {
     println!("{}", result);
     Ok(())
 }
+
+fn handle_name(fuzzy: bool, limit: usize, query: String) -> Result<()> {
+    let mut chars = if fuzzy {
+        fuzzy_find_chars_by_name(&query, limit)
+    } else {
+        find_chars_by_name(&query)
+    };
+    if chars.is_empty() {
+        return Err(anyhow::anyhow!("No characters found for: {}", query));
+    }
+    if chars.len() > limit {
+        eprintln!("Showing {} of {} characters (use --limit to show more)", limit, chars.len());
+        chars.truncate(limit);
+    }
+    print!("{}", format_codepoint_lines(&chars));
+    Ok(())
+}
+
+fn handle_chart(format: ChartFormat, category: Option<String>, block_or_range: String) -> Result<()> {
+    let lib_format = match format {
+        ChartFormat::Grid => LibChartFormat::Grid,
+        ChartFormat::Lines => LibChartFormat::Lines,
+        ChartFormat::Json => LibChartFormat::Json,
+    };
+    let chars = chart_chars(&block_or_range, category.as_deref())?;
+    print!("{}", format_chart(&chars, lib_format)?);
+    Ok(())
+}
+
+fn handle_undump(input: Vec<u8>) -> Result<()> {
+    let json = String::from_utf8(input)
+        .map_err(|_| anyhow::anyhow!("Dump input is not valid UTF-8 text"))?;
+    let output = undump_bytes(&json)?;
+    // Written as-is, without a trailing newline, so the output matches the dumped input exactly
+    std::io::stdout().write_all(&output)?;
+    Ok(())
+}
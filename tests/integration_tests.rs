@@ -380,3 +380,705 @@ fn test_complex_emoji_drop() {
         .success()
         .stdout(predicate::str::contains("👩‍🍳\n"));
 }
+
+// Tests for dump command
+#[test]
+fn test_dump_unicode_properties() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("dump")
+        .arg("ー")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("category: Modifier_Letter"))
+        .stdout(predicate::str::contains("script: Common (ext: Hiragana Katakana)"))
+        .stdout(predicate::str::contains("block: Katakana"));
+}
+
+#[test]
+fn test_dump_offsets_and_encodings() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("dump")
+        .arg("あ🍣")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Cluster 1: 🍣 (1 codepoint)  offset: byte 3, codepoint 1, utf-16 1"))
+        .stdout(predicate::str::contains("utf-8: E3 81 82  utf-16: 3042"));
+}
+
+#[test]
+fn test_dump_csv_format() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("dump")
+        .arg("--format")
+        .arg("csv")
+        .arg("あ,")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("cluster_index,codepoint_index,cluster,char,codepoint,name,"))
+        .stdout(predicate::str::contains("\n0,0,あ,あ,U+3042,HIRAGANA LETTER A,E3 81 82,3042,"))
+        .stdout(predicate::str::contains("\n1,0,\",\",\",\",U+002C,COMMA,"));
+}
+
+#[test]
+fn test_dump_tsv_no_header() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("dump")
+        .arg("--format")
+        .arg("tsv")
+        .arg("--no-header")
+        .arg("🍣")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("0\t0\t🍣\t🍣\tU+1F363\tSUSHI\t"));
+}
+
+#[test]
+fn test_dump_markdown_format() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("dump")
+        .arg("--format")
+        .arg("markdown")
+        .arg("❤️")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("| 0 | `❤\\ufe0f` | 0 | `❤` | U+2764 |"))
+        .stdout(predicate::str::contains("|  |  | 1 | `\\ufe0f` | U+FE0F |"));
+}
+
+#[test]
+fn test_dump_html_format() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("dump")
+        .arg("--format")
+        .arg("html")
+        .arg("--lang")
+        .arg("ja")
+        .arg("あ")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("<table class=\"mojibox-dump\" lang=\"ja\" dir=\"ltr\">"))
+        .stdout(predicate::str::contains("<code>あ</code>"));
+}
+
+#[test]
+fn test_dump_color_always() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("dump")
+        .arg("--color")
+        .arg("always")
+        .arg("a\u{202E}")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\x1b[97;41m[0] \\u202e    U+202E  RIGHT-TO-LEFT OVERRIDE  (alias: RLO)\x1b[0m"));
+}
+
+#[test]
+fn test_dump_color_auto_when_piped() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("dump")
+        .arg("a\u{FE0F}")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[1] \\ufe0f    U+FE0F  VARIATION SELECTOR-16"))
+        .stdout(predicate::str::contains("\x1b[").not());
+}
+
+#[test]
+fn test_dump_hex_input_with_invalid_sequence() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("dump")
+        .arg("--input-format")
+        .arg("hex")
+        .arg("E38182 ED A0 80")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Cluster 0: あ (1 codepoint)"))
+        .stdout(predicate::str::contains("Cluster 1: \\xED (invalid)  offset: byte 3, codepoint 1, utf-16 1"))
+        .stdout(predicate::str::contains("  bytes: ED  reason: encoded surrogate"))
+        .stdout(predicate::str::contains("  bytes: A0  reason: unexpected continuation byte"));
+}
+
+#[test]
+fn test_dump_raw_bytes_from_stdin() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("dump")
+        .arg("--format")
+        .arg("csv")
+        .arg("--no-header")
+        .write_stdin(&b"a\xFF"[..])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1,,\\xFF,,,<invalid: invalid byte (never used in UTF-8)>,FF,"));
+}
+
+#[test]
+fn test_dump_emoji_sequence_name() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("dump")
+        .arg("--format")
+        .arg("json")
+        .arg("👨‍💻🇯🇵")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"sequence_type\": \"zwj\""))
+        .stdout(predicate::str::contains("\"name\": \"man technologist\""))
+        .stdout(predicate::str::contains("\"sequence_type\": \"flag\""))
+        .stdout(predicate::str::contains("\"name\": \"flag: Japan\""));
+}
+
+#[test]
+fn test_dump_codepoint_labels_and_aliases() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("dump")
+        .arg("\u{7}\u{E000}\u{FFFF}\u{378}")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("U+0007  <control-0007>  (alias: ALERT, BEL, BELL)"))
+        .stdout(predicate::str::contains("U+E000  <private-use-E000>"))
+        .stdout(predicate::str::contains("U+FFFF  <noncharacter-FFFF>"))
+        .stdout(predicate::str::contains("U+0378  <reserved-0378>"))
+        .stdout(predicate::str::contains("UNKNOWN CHARACTER").not());
+}
+
+#[test]
+fn test_dump_unihan() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("dump")
+        .arg("--unihan")
+        .arg("漢字")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("unihan: mandarin: hàn  jis: 20-33  shift_jis: 8A BF"))
+        .stdout(predicate::str::contains("unihan: mandarin: zì  jis: 27-90  shift_jis: 8E 9A"));
+}
+
+#[test]
+fn test_undump_roundtrip() {
+    let mut dump = Command::cargo_bin("mojibox").unwrap();
+    let output = dump.arg("dump").arg("--format").arg("jsonl").arg("が👨‍💻").output().unwrap();
+
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("undump")
+        .write_stdin(output.stdout)
+        .assert()
+        .success()
+        .stdout("が👨‍💻");
+}
+
+#[test]
+fn test_undump_hand_written_fixture() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("undump")
+        .arg(r#"[{"display":"日","codepoints":[{"codepoint":"U+65E5"}]},{"display":"本","codepoints":[{"codepoint":"U+672C"}]}]"#)
+        .assert()
+        .success()
+        .stdout("日本");
+}
+
+#[test]
+fn test_undump_mismatch() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("undump")
+        .arg(r#"[{"cluster_index":0,"display":"x","byte_offset":0,"codepoint_offset":0,"utf16_offset":0,"codepoints":[]}]"#)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cluster 0: display \"x\" has no code points"));
+}
+
+#[test]
+fn test_dump_filters() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("dump")
+        .arg("--non-ascii")
+        .arg("--script")
+        .arg("Cyrillic")
+        .arg("pаypal 🍣")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Cluster 1: а (1 codepoint)  offset: byte 1, codepoint 1, utf-16 1"))
+        .stdout(predicate::str::contains("Cluster 0:").not())
+        .stdout(predicate::str::contains("SUSHI").not());
+}
+
+#[test]
+fn test_dump_filter_unknown_category() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("dump")
+        .arg("--category")
+        .arg("Foo")
+        .arg("a")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown general category: Foo"));
+}
+
+#[test]
+fn test_name_exact() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("name")
+        .arg("IDEOGRAPHIC SPACE")
+        .assert()
+        .success()
+        .stdout("[0] \\u3000    U+3000  IDEOGRAPHIC SPACE\n");
+}
+
+#[test]
+fn test_name_wildcard_with_limit() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("name")
+        .arg("--limit")
+        .arg("2")
+        .arg("HIRAGANA LETTER SMALL *")
+        .assert()
+        .success()
+        .stdout("[0] ぁ    U+3041  HIRAGANA LETTER SMALL A\n[1] ぃ    U+3043  HIRAGANA LETTER SMALL I\n")
+        .stderr(predicate::str::contains("Showing 2 of"));
+}
+
+#[test]
+fn test_name_not_found() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("name")
+        .arg("XYZZYQ")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No characters found for: XYZZYQ"));
+}
+
+#[test]
+fn test_escape_named_format() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("escape")
+        .arg("--format")
+        .arg("named")
+        .arg("あ🍣")
+        .assert()
+        .success()
+        .stdout("\\N{HIRAGANA LETTER A}\\N{SUSHI}\n");
+}
+
+#[test]
+fn test_unescape_named() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("unescape")
+        .arg("\\N{HIRAGANA LETTER A}\\u{1F363}")
+        .assert()
+        .success()
+        .stdout("あ🍣\n");
+}
+
+#[test]
+fn test_chart_block_grid() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("chart")
+        .arg("Katakana")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("         0  1  2  3  4  5  6  7  8  9  A  B  C  D  E  F\n"))
+        .stdout(predicate::str::contains("U+30A0   ゠ ァ ア ィ イ ゥ ウ ェ エ ォ オ カ ガ キ ギ ク\n"));
+}
+
+#[test]
+fn test_chart_range_lines_with_category() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("chart")
+        .arg("--format")
+        .arg("lines")
+        .arg("--category")
+        .arg("Nd")
+        .arg("U+0030..U+0041")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("[0] 0    U+0030  DIGIT ZERO\n"))
+        .stdout(predicate::str::contains("[9] 9    U+0039  DIGIT NINE\n"))
+        .stdout(predicate::str::contains("LATIN").not());
+}
+
+#[test]
+fn test_ord_notation_html_hex() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("ord")
+        .arg("--notation")
+        .arg("html-hex")
+        .arg("漢🍺")
+        .assert()
+        .success()
+        .stdout("&#x6F22; &#x1F37A;\n");
+}
+
+#[test]
+fn test_ord_grapheme_groups() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("ord")
+        .arg("-n")
+        .arg("unicode")
+        .arg("--grapheme")
+        .arg("a👨‍💻")
+        .assert()
+        .success()
+        .stdout("a\tU+0061\n👨‍💻\tU+1F468 U+200D U+1F4BB\n");
+}
+
+#[test]
+fn test_chr_mixed_notations_and_range() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("chr")
+        .arg("U+3042")
+        .arg("&#12356;")
+        .arg("U+3094..U+3096")
+        .assert()
+        .success()
+        .stdout("あいゔゕゖ\n");
+}
+
+#[test]
+fn test_chr_invalid_token_position() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("chr")
+        .arg("U+3042")
+        .arg("U+ZZZZ")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid code point at token 2: U+ZZZZ"));
+}
+
+#[test]
+fn test_hex2bin_raw_mode() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("hex2bin")
+        .arg("--mode")
+        .arg("raw")
+        .arg("41 FF 42")
+        .assert()
+        .success()
+        .stdout(&b"A\xFFB"[..]);
+}
+
+#[test]
+fn test_hex2bin_reads_wrapped_literals() {
+    for input in ["b\"\\xE3\\x81\\\n  \\x82\"", "[]byte{\n\t0xE3, 0x81,\n\t0x82,\n}", "new Uint8Array([0xE3, 0x81, 0x82])"] {
+        let mut cmd = Command::cargo_bin("mojibox").unwrap();
+        cmd.arg("hex2bin").arg(input).assert().success().stdout("あ\n");
+    }
+}
+
+#[test]
+fn test_hex2bin_escaped_mode() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("hex2bin")
+        .arg("-m")
+        .arg("escaped")
+        .arg("E3 81 82 E3 81")
+        .assert()
+        .success()
+        .stdout("あ\\xE3\\x81\n");
+}
+
+#[test]
+fn test_hex2bin_output_file() {
+    let path = std::env::temp_dir().join(format!("mojibox-hex2bin-{}.bin", std::process::id()));
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("hex2bin")
+        .arg("--mode")
+        .arg("raw")
+        .arg("--output")
+        .arg(&path)
+        .arg("\\xF0\\x9F\\x8D")
+        .assert()
+        .success()
+        .stdout("");
+    let written = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(written, b"\xF0\x9F\x8D");
+}
+
+#[test]
+fn test_hexdump_stdin_with_invalid_bytes() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("hexdump")
+        .arg("--color")
+        .arg("never")
+        .write_stdin(&b"\xE3\x81\x82\xFF"[..])
+        .assert()
+        .success()
+        .stdout("00000000  E3 81 82 FF                                       |あ·\u{FFFD}|\n00000004\n");
+}
+
+#[test]
+fn test_hexdump_hex_input() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("hexdump")
+        .arg("--input-format")
+        .arg("hex")
+        .arg("--lower")
+        .arg("F0 9F 8D A3")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("00000000  f0 9f 8d a3 "))
+        .stdout(predicate::str::contains("|🍣··|"));
+}
+
+#[test]
+fn test_encode_decode_base64_roundtrip() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    let output = cmd.arg("encode").arg("日本語").output().unwrap();
+    assert_eq!(output.stdout, b"5pel5pys6Kqe\n");
+
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("decode")
+        .write_stdin(output.stdout)
+        .assert()
+        .success()
+        .stdout("日本語\n");
+}
+
+#[test]
+fn test_encode_base64url_no_pad_from_hex() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("encode")
+        .arg("--encoding")
+        .arg("base64url")
+        .arg("--no-pad")
+        .arg("--input-format")
+        .arg("hex")
+        .arg("FB FF")
+        .assert()
+        .success()
+        .stdout("-_8\n");
+}
+
+#[test]
+fn test_decode_invalid_utf8_modes() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("decode")
+        .arg("Qf8=")
+        .assert()
+        .success()
+        .stdout("A\u{FFFD}\n");
+
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("decode")
+        .arg("--mode")
+        .arg("raw")
+        .arg("Qf8=")
+        .assert()
+        .success()
+        .stdout(&b"A\xFF"[..]);
+}
+
+#[test]
+fn test_decode_base32_and_ascii85() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("decode")
+        .arg("-e")
+        .arg("base32")
+        .arg("NBSWY3DP")
+        .assert()
+        .success()
+        .stdout("hello\n");
+
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("decode")
+        .arg("-e")
+        .arg("ascii85")
+        .arg("<~87cURD]i,\"Ebo80~>")
+        .assert()
+        .success()
+        .stdout("Hello World!\n");
+}
+
+#[test]
+fn test_urlencode_form() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("urlencode")
+        .arg("--set")
+        .arg("form")
+        .arg("ラーメン 大盛り&")
+        .assert()
+        .success()
+        .stdout("%E3%83%A9%E3%83%BC%E3%83%A1%E3%83%B3+%E5%A4%A7%E7%9B%9B%E3%82%8A%26\n");
+}
+
+#[test]
+fn test_urldecode_stdin_form() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("urldecode")
+        .arg("--form")
+        .write_stdin("q=%E6%9D%B1%E4%BA%AC+%E9%A7%85\n")
+        .assert()
+        .success()
+        .stdout("q=東京 駅\n");
+}
+
+#[test]
+fn test_urldecode_strict_rejects_invalid_utf8() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("urldecode")
+        .arg("--mode")
+        .arg("strict")
+        .arg("%E6%97")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid UTF-8 sequence"));
+
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("urldecode")
+        .arg("%E6%97a")
+        .assert()
+        .success()
+        .stdout("\u{FFFD}a\n");
+}
+
+#[test]
+fn test_hex2bin_c_array() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("hex2bin")
+        .arg("{0xE3, 0x81, 0x82}")
+        .assert()
+        .success()
+        .stdout("あ\n");
+}
+
+#[test]
+fn test_hex2bin_strict_reports_offsets() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("hex2bin")
+        .arg("--parse")
+        .arg("strict")
+        .arg("E3 81,82")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("offset 5: , (separator not allowed in strict mode)"));
+}
+
+#[test]
+fn test_bin2hex_go_with_width() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("bin2hex")
+        .arg("--format")
+        .arg("go")
+        .arg("--width")
+        .arg("3")
+        .arg("あい")
+        .assert()
+        .success()
+        .stdout("[]byte{\n\t0xE3, 0x81, 0x82,\n\t0xE3, 0x81, 0x84,\n}\n");
+}
+
+#[test]
+fn test_bin2hex_python_literal() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("bin2hex")
+        .arg("-f")
+        .arg("python")
+        .arg("--lower")
+        .arg("あ")
+        .assert()
+        .success()
+        .stdout("b\"\\xe3\\x81\\x82\"\n");
+}
+
+#[test]
+fn test_explain_encoding_command() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("explain-encoding")
+        .arg("あ")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("  UTF-8       1110xxxx 10xxxxxx 10xxxxxx\n                  0011   000001   000010\n"))
+        .stdout(predicate::str::contains("              E3       81       82\n"))
+        .stdout(predicate::str::contains("              3042\n"));
+}
+
+#[test]
+fn test_scrub_escape_policy_reports_count() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("scrub")
+        .arg("--input-format")
+        .arg("hex")
+        .arg("--policy")
+        .arg("escape")
+        .arg("E3 81 82 E3 81")
+        .assert()
+        .success()
+        .stdout("あ\\xE3\\x81\n")
+        .stderr("2 replacements made\n");
+}
+
+#[test]
+fn test_scrub_replace_policy_from_stdin() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("scrub")
+        .arg("--policy")
+        .arg("replace")
+        .arg("--replacement")
+        .arg("[?]")
+        .write_stdin(&b"a\xFFb\n"[..])
+        .assert()
+        .success()
+        .stdout("a[?]b\n")
+        .stderr("1 replacement made\n");
+}
+
+#[test]
+fn test_scrub_surrogate_escape_policy() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("scrub")
+        .arg("--input-format")
+        .arg("hex")
+        .arg("-p")
+        .arg("surrogate-escape")
+        .arg("41 80 FF")
+        .assert()
+        .success()
+        .stdout(&b"A\xED\xB2\x80\xED\xB3\xBF\n"[..]);
+}
+
+#[test]
+fn test_validate_valid_input() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("validate")
+        .arg("日本語")
+        .assert()
+        .success()
+        .stdout("<input>: valid UTF-8 (9 bytes)\n");
+}
+
+#[test]
+fn test_validate_invalid_stdin_fails() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("validate")
+        .write_stdin(&b"line 1\nab\xED\xA0\x80c\xE3\x81\n"[..])
+        .assert()
+        .code(1)
+        .stdout("<stdin>:2:3: encoded surrogate: ED A0 80 (byte 9)\n<stdin>:2:5: truncated sequence: E3 81 (byte 13)\n")
+        .stderr(predicate::str::contains("<stdin>: invalid UTF-8 (2 errors)"));
+}
+
+#[test]
+fn test_validate_file() {
+    let path = std::env::temp_dir().join(format!("mojibox-validate-{}.txt", std::process::id()));
+    std::fs::write(&path, b"\xF0\x9F\x8D").unwrap();
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    let assert = cmd.arg("validate").arg("--file").arg(&path).assert();
+    std::fs::remove_file(&path).unwrap();
+    assert
+        .failure()
+        .stdout(predicate::str::ends_with(":1:1: truncated sequence: F0 9F 8D (byte 0)\n"));
+}
+
+#[test]
+fn test_validate_invalid_bytes() {
+    let mut cmd = Command::cargo_bin("mojibox").unwrap();
+    cmd.arg("validate")
+        .arg("--input-format")
+        .arg("hex")
+        .arg("FE FF F8")
+        .assert()
+        .code(1)
+        .stdout(concat!(
+            "<input>:1:1: invalid byte (never used in UTF-8): FE (byte 0)\n",
+            "<input>:1:2: invalid byte (never used in UTF-8): FF (byte 1)\n",
+            "<input>:1:3: invalid byte (never used in UTF-8): F8 (byte 2)\n",
+        ));
+}
@@ -0,0 +1,180 @@
+#!/usr/bin/env python3
+"""Generate src/ucd_tables.rs.
+
+Age and Numeric_Value are not covered by icu_properties, so they are read from the
+Unicode Character Database files of the pinned version below, which matches the Unicode
+version of the icu_properties data. The files are downloaded from unicode.org into
+tools/ucd-<version>/ the first time (or read from --ucd-dir), and the SHA-256 of every
+file read is written to the header of the generated file, so a regeneration from the
+same files gives the same output and a changed file shows up in the diff.
+
+Name aliases are read from NameAliases.txt of the same version. The Unicode 1.0 names of
+control characters (from UnicodeData.txt), which have no Name property of their own, are
+added as aliases too.
+
+Names of the characters new in this version are read from UnicodeData.txt, as
+unicode_names2 only covers Unicode 15.0. Names never change once assigned. The names of
+ideographs in ranges, such as CJK UNIFIED IDEOGRAPH-2EBF0, are derived from the code point
+instead.
+
+Mandarin readings of unified ideographs are the kMandarin field of Unihan_Readings.txt in
+Unihan.zip of the same version.
+
+Usage: python3 tools/gen_ucd_tables.py [--ucd-dir DIR] > src/ucd_tables.rs
+"""
+
+import argparse
+import hashlib
+import io
+import os
+import sys
+import urllib.request
+import zipfile
+
+UCD_VERSION = "15.1.0"
+UCD_URL = "https://www.unicode.org/Public/%s/ucd/" % UCD_VERSION
+
+
+class Ucd:
+    """The pinned UCD files, downloaded on first use, with the checksums of those read."""
+
+    def __init__(self, directory):
+        self.directory = directory
+        self.checksums = {}
+
+    def path(self, name):
+        path = os.path.join(self.directory, name)
+        if not os.path.exists(path):
+            os.makedirs(os.path.dirname(path), exist_ok=True)
+            print("Downloading %s%s" % (UCD_URL, name), file=sys.stderr)
+            urllib.request.urlretrieve(UCD_URL + name, path)
+        with open(path, "rb") as f:
+            self.checksums[name] = hashlib.sha256(f.read()).hexdigest()
+        return path
+
+    # Code point, field name and value from a member of Unihan.zip, such as Unihan_Readings.txt
+    def unihan(self, member):
+        with zipfile.ZipFile(self.path("Unihan.zip")) as archive:
+            for line in io.TextIOWrapper(archive.open(member), encoding="utf-8"):
+                if line.startswith("U+"):
+                    cp, field, value = line.rstrip("\n").split("\t")
+                    yield int(cp[2:], 16), field, value
+
+    # Data lines split on ";" with comments and surrounding spaces removed
+    def records(self, name):
+        with open(self.path(name), encoding="utf-8") as f:
+            for line in f:
+                line = line.split("#", 1)[0].strip()
+                if line:
+                    yield [field.strip() for field in line.split(";")]
+
+
+def code_point_range(field):
+    start, _, end = field.partition("..")
+    return int(start, 16), int(end or start, 16)
+
+
+def merge_ranges(records):
+    result = []
+    for start, end, value in sorted(records):
+        if result and result[-1][2] == value and result[-1][1] == start - 1:
+            result[-1][1] = end
+        else:
+            result.append([start, end, value])
+    return result
+
+
+def age(ucd):
+    return merge_ranges(code_point_range(cp) + (value,) for cp, value in ucd.records("DerivedAge.txt"))
+
+
+# The fourth field is the exact value, an integer or a fraction such as "1/2" or "-1/2"
+def numeric_values(ucd):
+    return merge_ranges(
+        code_point_range(cp) + (rational,)
+        for cp, _, _, rational in ucd.records("extracted/DerivedNumericValues.txt")
+    )
+
+
+# Characters of the pinned version that have a name of their own, not a range such as
+# <CJK Ideograph Extension I, First>
+def new_names(ucd):
+    version = UCD_VERSION.rsplit(".", 1)[0]
+    new = [code_point_range(cp) for cp, value in ucd.records("DerivedAge.txt") if value == version]
+    return sorted(
+        (int(fields[0], 16), fields[1])
+        for fields in ucd.records("UnicodeData.txt")
+        if not fields[1].startswith("<") and any(start <= int(fields[0], 16) <= end for start, end in new)
+    )
+
+
+# Aliases of every type (correction, control, alternate, figment, abbreviation) in file order
+def name_aliases(ucd):
+    aliases = {}
+    for cp, alias, _ in ucd.records("NameAliases.txt"):
+        aliases.setdefault(int(cp, 16), []).append(alias)
+    for fields in ucd.records("UnicodeData.txt"):
+        cp, unicode_1_name = int(fields[0], 16), fields[10]
+        if unicode_1_name and (cp <= 0x1F or 0x7F <= cp <= 0x9F) and unicode_1_name not in aliases.get(cp, []):
+            aliases.setdefault(cp, []).append(unicode_1_name)
+    return sorted(aliases.items())
+
+
+# kMandarin has the most customary reading first, and a second one where the usual
+# reading differs between mainland China and Taiwan
+def mandarin_readings(ucd):
+    return sorted(
+        (cp, value.split(" ")[0])
+        for cp, field, value in ucd.unihan("Unihan_Readings.txt")
+        if field == "kMandarin"
+    )
+
+
+def main():
+    parser = argparse.ArgumentParser(description="Generate src/ucd_tables.rs")
+    parser.add_argument("--ucd-dir", default=os.path.join(os.path.dirname(__file__), "ucd-" + UCD_VERSION),
+                        help="directory with the UCD %s files (downloaded if missing)" % UCD_VERSION)
+    ucd = Ucd(parser.parse_args().ucd_dir)
+
+    tables = [
+        ("AGE", age(ucd)),
+        ("NUMERIC_VALUE", numeric_values(ucd)),
+    ]
+    names = new_names(ucd)
+    aliases = name_aliases(ucd)
+    mandarin = mandarin_readings(ucd)
+
+    print("// Generated by tools/gen_ucd_tables.py. Do not edit by hand.")
+    print("//")
+    print("// Age, Numeric_Value, name aliases and Mandarin readings (Unihan kMandarin) from the")
+    print("// Unicode Character Database %s:" % UCD_VERSION)
+    for name, checksum in sorted(ucd.checksums.items()):
+        print("//   %-36s sha256:%s" % (name, checksum))
+    print("// Names of the characters new in %s, and name aliases, which include the Unicode 1.0"
+          % UCD_VERSION.rsplit(".", 1)[0])
+    print("// names of control characters.")
+    for name, ranges in tables:
+        print()
+        print("pub(crate) const %s: &[(u32, u32, &str)] = &[" % name)
+        for start, end, value in ranges:
+            print('    (0x%X, 0x%X, "%s"),' % (start, end, value))
+        print("];")
+    print()
+    print("pub(crate) const NAMES: &[(u32, &str)] = &[")
+    for cp, name in names:
+        print('    (0x%X, "%s"),' % (cp, name))
+    print("];")
+    print()
+    print("pub(crate) const NAME_ALIASES: &[(u32, &[&str])] = &[")
+    for cp, values in aliases:
+        print("    (0x%X, &[%s])," % (cp, ", ".join('"%s"' % v for v in values)))
+    print("];")
+    print()
+    print("pub(crate) const MANDARIN: &[(u32, &str)] = &[")
+    for cp, reading in mandarin:
+        print('    (0x%X, "%s"),' % (cp, reading))
+    print("];")
+
+
+if __name__ == "__main__":
+    main()